@@ -0,0 +1,422 @@
+use crate::models::{
+    team_to_slug, with_derived_fields, with_result_ids, ApiResponse, MatchResult, MatchSegment,
+    NewsArticle, PolymarketOdds, RankedTeam, TeamProfile,
+};
+use chrono::NaiveDate;
+
+// Test mode flag - set to true to use mock data
+pub const USE_TEST_DATA: bool = false; // Change to false to use real API
+
+pub fn get_test_matches() -> Vec<MatchSegment> {
+    vec![
+        MatchSegment {
+            team1: "Sentinels".to_string(),
+            team2: "100 Thieves".to_string(),
+            score1: "13".to_string(),
+            score2: "9".to_string(),
+            current_map: "Ascent".to_string(),
+            match_event: "VCT 2025: Americas Stage 1".to_string(),
+            match_series: "Regular Season".to_string(),
+            time_until_match: "LIVE".to_string(),
+            flag1: "flag_us".to_string(),
+            flag2: "flag_us".to_string(),
+            team1_logo: "https://owcdn.net/img/62e7a0e8f1c0b.png".to_string(),
+            team2_logo: "https://owcdn.net/img/62e7a0e8f1c0b.png".to_string(),
+            team1_round_ct: "7".to_string(),
+            team1_round_t: "6".to_string(),
+            team2_round_ct: "5".to_string(),
+            team2_round_t: "4".to_string(),
+            map_number: "1".to_string(),
+            unix_timestamp: "1713996000".to_string(),
+            match_page: "https://www.vlr.gg/12345".to_string(),
+            match_id: String::new(),
+            starts_at: None,
+            start_time: String::new(),
+            rank1: None,
+            rank2: None,
+            country1: None,
+            country2: None,
+        },
+        MatchSegment {
+            team1: "Fnatic".to_string(),
+            team2: "Team Liquid".to_string(),
+            score1: "7".to_string(),
+            score2: "5".to_string(),
+            current_map: "Bind".to_string(),
+            match_event: "VCT 2025: EMEA Stage 1".to_string(),
+            match_series: "Regular Season".to_string(),
+            time_until_match: "LIVE".to_string(),
+            flag1: String::new(),
+            flag2: String::new(),
+            team1_logo: String::new(),
+            team2_logo: String::new(),
+            team1_round_ct: String::new(),
+            team1_round_t: String::new(),
+            team2_round_ct: String::new(),
+            team2_round_t: String::new(),
+            map_number: "2".to_string(),
+            unix_timestamp: String::new(),
+            match_page: String::new(),
+            match_id: String::new(),
+            starts_at: None,
+            start_time: String::new(),
+            rank1: None,
+            rank2: None,
+            country1: None,
+            country2: None,
+        },
+        MatchSegment {
+            team1: "Paper Rex".to_string(),
+            team2: "DRX".to_string(),
+            score1: "10".to_string(),
+            score2: "8".to_string(),
+            current_map: "Icebox".to_string(),
+            match_event: "VCT 2025: Pacific Stage 1".to_string(),
+            match_series: "Regular Season".to_string(),
+            time_until_match: "LIVE".to_string(),
+            flag1: String::new(),
+            flag2: String::new(),
+            team1_logo: String::new(),
+            team2_logo: String::new(),
+            team1_round_ct: String::new(),
+            team1_round_t: String::new(),
+            team2_round_ct: String::new(),
+            team2_round_t: String::new(),
+            map_number: "1".to_string(),
+            unix_timestamp: String::new(),
+            match_page: String::new(),
+            match_id: String::new(),
+            starts_at: None,
+            start_time: String::new(),
+            rank1: None,
+            rank2: None,
+            country1: None,
+            country2: None,
+        },
+        MatchSegment {
+            team1: "LOUD".to_string(),
+            team2: "KRÜ Esports".to_string(),
+            score1: "6".to_string(),
+            score2: "6".to_string(),
+            current_map: "Lotus".to_string(),
+            match_event: "VCT 2025: Americas Stage 1".to_string(),
+            match_series: "Regular Season".to_string(),
+            time_until_match: "LIVE".to_string(),
+            flag1: String::new(),
+            flag2: String::new(),
+            team1_logo: String::new(),
+            team2_logo: String::new(),
+            team1_round_ct: String::new(),
+            team1_round_t: String::new(),
+            team2_round_ct: String::new(),
+            team2_round_t: String::new(),
+            map_number: "3".to_string(),
+            unix_timestamp: String::new(),
+            match_page: String::new(),
+            match_id: String::new(),
+            starts_at: None,
+            start_time: String::new(),
+            rank1: None,
+            rank2: None,
+            country1: None,
+            country2: None,
+        },
+        MatchSegment {
+            team1: "G2 Esports".to_string(),
+            team2: "KOI".to_string(),
+            score1: "12".to_string(),
+            score2: "11".to_string(),
+            current_map: "Split".to_string(),
+            match_event: "VCT 2025: EMEA Stage 1".to_string(),
+            match_series: "Regular Season".to_string(),
+            time_until_match: "LIVE".to_string(),
+            flag1: String::new(),
+            flag2: String::new(),
+            team1_logo: String::new(),
+            team2_logo: String::new(),
+            team1_round_ct: String::new(),
+            team1_round_t: String::new(),
+            team2_round_ct: String::new(),
+            team2_round_t: String::new(),
+            map_number: "2".to_string(),
+            unix_timestamp: String::new(),
+            match_page: String::new(),
+            match_id: String::new(),
+            starts_at: None,
+            start_time: String::new(),
+            rank1: None,
+            rank2: None,
+            country1: None,
+            country2: None,
+        },
+    ]
+}
+
+// Sample upcoming matches relative to now, including one after midnight UTC
+pub fn get_test_upcoming_matches() -> Vec<MatchSegment> {
+    let now = chrono::Utc::now();
+    let tomorrow = (now + chrono::Duration::days(1)).date_naive();
+    let upcoming = |team1: &str, team2: &str, start: chrono::DateTime<chrono::Utc>, page: &str| MatchSegment {
+        team1: team1.to_string(),
+        team2: team2.to_string(),
+        score1: String::new(),
+        score2: String::new(),
+        current_map: String::new(),
+        match_event: "VCT 2025: Americas Stage 1".to_string(),
+        match_series: "Regular Season".to_string(),
+        time_until_match: format!("{} from now", crate::schedule::countdown(start, now)),
+        flag1: String::new(),
+        flag2: String::new(),
+        team1_logo: String::new(),
+        team2_logo: String::new(),
+        team1_round_ct: String::new(),
+        team1_round_t: String::new(),
+        team2_round_ct: String::new(),
+        team2_round_t: String::new(),
+        map_number: String::new(),
+        unix_timestamp: start.format("%Y-%m-%d %H:%M:%S").to_string(),
+        match_page: page.to_string(),
+        match_id: String::new(),
+        starts_at: None,
+        start_time: String::new(),
+        rank1: None,
+        rank2: None,
+        country1: None,
+        country2: None,
+    };
+    vec![
+        upcoming("MIBR", "NRG", now + chrono::Duration::minutes(95), "https://www.vlr.gg/12350"),
+        upcoming(
+            "Cloud9",
+            "Evil Geniuses",
+            tomorrow.and_hms_opt(0, 30, 0).unwrap().and_utc(),
+            "https://www.vlr.gg/12351",
+        ),
+    ]
+}
+
+// Sample completed matches, most recent first like the results feed
+pub fn get_test_results() -> Vec<MatchResult> {
+    let result = |team1: &str, team2: &str, score1: &str, score2: &str, page: &str| MatchResult {
+        team1: team1.to_string(),
+        team2: team2.to_string(),
+        score1: score1.to_string(),
+        score2: score2.to_string(),
+        flag1: String::new(),
+        flag2: String::new(),
+        time_completed: String::new(),
+        round_info: "Regular Season".to_string(),
+        tournament_name: "VCT 2025: Americas Stage 1".to_string(),
+        match_page: page.to_string(),
+        tournament_icon: String::new(),
+        match_id: String::new(),
+    };
+    vec![
+        result("Sentinels", "100 Thieves", "2", "1", "https://www.vlr.gg/12340"),
+        result("MIBR", "NRG", "0", "2", "https://www.vlr.gg/12339"),
+        result("Sentinels", "NRG", "2", "0", "https://www.vlr.gg/12338"),
+    ]
+}
+
+// Sample ranking for a region; the same teams whichever region is asked for
+pub fn get_test_rankings() -> Vec<RankedTeam> {
+    let team = |rank: u32, team: &str, record: &str| RankedTeam {
+        rank,
+        team: team.to_string(),
+        country: "United States".to_string(),
+        last_played: "2d ago".to_string(),
+        last_played_team: String::new(),
+        last_played_team_logo: String::new(),
+        record: record.to_string(),
+        earnings: String::new(),
+        logo: String::new(),
+    };
+    vec![
+        team(1, "Sentinels", "12-3"),
+        team(2, "G2 Esports", "11-4"),
+        team(3, "100 Thieves", "9-6"),
+        team(4, "NRG", "8-7"),
+    ]
+}
+
+pub fn get_test_news() -> Vec<NewsArticle> {
+    let article = |title: &str, path: &str| NewsArticle {
+        title: title.to_string(),
+        description: String::new(),
+        date: "April 4, 2025".to_string(),
+        author: "vlr.gg".to_string(),
+        url_path: path.to_string(),
+    };
+    vec![
+        article("Sentinels extend win streak in Americas Stage 1", "/451001/sentinels-extend-win-streak"),
+        article("G2 Esports announce roster change", "/451002/g2-esports-announce-roster-change"),
+    ]
+}
+
+pub const VLRGG_API_URL: &str = "https://vlrggapi.vercel.app";
+pub const POLYMARKET_API_URL: &str = "https://gamma-api.polymarket.com";
+// vlr.gg itself, for data the API doesn't expose such as per-map player stats
+pub const VLR_URL: &str = "https://www.vlr.gg";
+
+pub fn live_score_url(base: &str) -> String {
+    format!("{}/v2/match?q=live_score", base)
+}
+
+pub fn upcoming_url(base: &str) -> String {
+    format!("{}/v2/match?q=upcoming", base)
+}
+
+pub fn results_url(base: &str) -> String {
+    format!("{}/v2/match?q=results", base)
+}
+
+pub fn rankings_url(base: &str, region: &str) -> String {
+    format!("{}/v2/rankings?region={}", base, urlencoding::encode(region))
+}
+
+pub fn news_url(base: &str) -> String {
+    format!("{}/v2/news", base)
+}
+
+pub fn team_url(base: &str, team_id: &str) -> String {
+    format!("{}/v2/team?id={}", base, urlencoding::encode(team_id))
+}
+
+// Path of a match page, whether `match_page` is a full vlr.gg URL or already a path
+pub fn match_page_path(match_page: &str) -> Option<String> {
+    let page = match_page.trim();
+    let path = match page.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or(""),
+        None => page,
+    };
+    let path = path.trim_matches('/');
+    (!path.is_empty()).then(|| format!("/{}", path))
+}
+
+// Parse a live_score or upcoming response body into matches with their ids filled in
+pub fn parse_live_matches(body: &str) -> Result<Vec<MatchSegment>, serde_json::Error> {
+    let api_response: ApiResponse = serde_json::from_str(body)?;
+    Ok(with_derived_fields(api_response.data.segments))
+}
+
+// Parse a results response body into completed matches with their ids filled in
+pub fn parse_results(body: &str) -> Result<Vec<MatchResult>, serde_json::Error> {
+    let api_response: ApiResponse<MatchResult> = serde_json::from_str(body)?;
+    Ok(with_result_ids(api_response.data.segments))
+}
+
+pub fn parse_rankings(body: &str) -> Result<Vec<RankedTeam>, serde_json::Error> {
+    let api_response: ApiResponse<RankedTeam> = serde_json::from_str(body)?;
+    Ok(api_response.data.segments)
+}
+
+pub fn parse_news(body: &str) -> Result<Vec<NewsArticle>, serde_json::Error> {
+    let api_response: ApiResponse<NewsArticle> = serde_json::from_str(body)?;
+    Ok(api_response.data.segments)
+}
+
+// Parse a team response body; the profile is its only segment
+pub fn parse_team(body: &str) -> Result<Option<TeamProfile>, serde_json::Error> {
+    let api_response: ApiResponse<TeamProfile> = serde_json::from_str(body)?;
+    Ok(api_response.data.segments.into_iter().next())
+}
+
+// Polymarket game page for a match on `date`, used when no market is found
+pub fn fallback_market_url(team1: &str, team2: &str, date: NaiveDate) -> String {
+    format!(
+        "https://polymarket.com/sports/valorant/games/week/1/val-{}-{}-{}",
+        team_to_slug(team1),
+        team_to_slug(team2),
+        date.format("%Y-%m-%d")
+    )
+}
+
+// Polymarket search query and URL for a match
+pub fn polymarket_search_query(team1: &str, team2: &str) -> String {
+    format!("{} vs {}", team1, team2)
+}
+
+pub fn polymarket_search_url(base: &str, query: &str) -> String {
+    format!(
+        "{}/public-search?q={}&limit=10",
+        base,
+        urlencoding::encode(query)
+    )
+}
+
+// Odds for a match from a Polymarket search response body.
+// `None` means the search request failed; only the market URL is returned then.
+pub fn parse_polymarket_odds(
+    team1: &str,
+    team2: &str,
+    date: NaiveDate,
+    body: Option<&str>,
+) -> Result<PolymarketOdds, serde_json::Error> {
+    let Some(body) = body else {
+        return Ok(PolymarketOdds {
+            team1_odds: None,
+            team2_odds: None,
+            market_url: Some(fallback_market_url(team1, team2, date)),
+        });
+    };
+    let search_results: serde_json::Value = serde_json::from_str(body)?;
+    
+    // Try to find a market matching the teams
+    if let Some(results) = search_results.get("results").and_then(|r| r.as_array()) {
+        for result in results {
+            if let Some(question) = result.get("question").and_then(|q| q.as_str()) {
+                let question_lower = question.to_lowercase();
+                if question_lower.contains(&team1.to_lowercase()) && 
+                   question_lower.contains(&team2.to_lowercase()) {
+                    // Found a matching market
+                    if let Some(outcomes) = result.get("outcomes").and_then(|o| o.as_array()) {
+                        let mut team1_odds = None;
+                        let mut team2_odds = None;
+                        
+                        for outcome in outcomes {
+                            if let (Some(title), Some(price_str)) = (
+                                outcome.get("title").and_then(|t| t.as_str()),
+                                outcome.get("price").and_then(|p| p.as_str()),
+                            ) {
+                                let price = price_str.parse::<f64>().ok();
+                                let title_lower = title.to_lowercase();
+                                
+                                if title_lower.contains(&team1.to_lowercase()) {
+                                    team1_odds = price;
+                                } else if title_lower.contains(&team2.to_lowercase()) {
+                                    team2_odds = price;
+                                }
+                            }
+                        }
+                        
+                        let slug = result.get("slug").and_then(|s| s.as_str()).map(|s| s.to_string());
+                        let market_url = slug.map(|s| format!("https://polymarket.com/{}", s))
+                            .or_else(|| Some(fallback_market_url(team1, team2, date)));
+                        
+                        return Ok(PolymarketOdds {
+                            team1_odds,
+                            team2_odds,
+                            market_url,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    
+    // If no market found, return URL only
+    Ok(PolymarketOdds {
+        team1_odds: None,
+        team2_odds: None,
+        market_url: Some(fallback_market_url(team1, team2, date)),
+    })
+}
+
+// Dummy Polymarket odds for UI preview (e.g. "Will Jesus Christ return before 2027?" — 4% Yes / 96% No)
+pub fn get_dummy_polymarket_odds() -> PolymarketOdds {
+    PolymarketOdds {
+        team1_odds: Some(0.04),
+        team2_odds: Some(0.96),
+        market_url: Some("https://polymarket.com/event/will-jesus-christ-return-before-2027".to_string()),
+    }
+}
@@ -0,0 +1,5 @@
+// Standalone build of the headless CLI, without the Tauri app
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(valo_core::cli::run(&args));
+}
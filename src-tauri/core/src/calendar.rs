@@ -0,0 +1,207 @@
+// iCalendar (RFC 5545) feed of upcoming matches for followed teams and events.
+// Each match keeps the same UID across exports; its SEQUENCE is bumped whenever the
+// start time moves, so subscribed calendars replace the event instead of duplicating it.
+use crate::persist::{load_json, save_json};
+use crate::settings::{CalendarSettings, FollowSettings};
+use crate::snapshot::MatchSnapshot;
+use crate::MatchSegment;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const PRODID: &str = "-//valo-match-checker//Valorant matches//EN";
+const UID_DOMAIN: &str = "valo-match-checker";
+// Entries for matches that started this long ago are dropped from the state file
+const ENTRY_RETENTION_DAYS: i64 = 30;
+
+// Last start time seen for a match and how often it has moved
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CalendarEntry {
+    pub starts_at: i64,
+    pub sequence: u32,
+}
+
+// Calendar settings and per-match revision state, persisted so sequence numbers
+// keep increasing across restarts
+pub struct CalendarFeed {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<String, CalendarEntry>>,
+    settings: Mutex<(FollowSettings, CalendarSettings)>,
+}
+
+impl CalendarFeed {
+    pub fn load(path: Option<PathBuf>, follow: FollowSettings, calendar: CalendarSettings) -> Self {
+        let entries = load_json(path.as_deref());
+        CalendarFeed {
+            path,
+            entries: Mutex::new(entries),
+            settings: Mutex::new((follow, calendar)),
+        }
+    }
+
+    pub fn set_settings(&self, follow: FollowSettings, calendar: CalendarSettings) {
+        *self.settings.lock().unwrap() = (follow, calendar);
+    }
+
+    fn save(&self, entries: &HashMap<String, CalendarEntry>) {
+        save_json(self.path.as_deref(), entries, "calendar state");
+    }
+
+    // Followed matches with a known start time, live ones included so they stay in
+    // the calendar until they finish
+    pub fn followed_matches(&self, snapshot: &MatchSnapshot) -> Vec<MatchSegment> {
+        let (follow, _) = self.settings.lock().unwrap().clone();
+        let mut seen = Vec::new();
+        snapshot
+            .matches
+            .iter()
+            .chain(&snapshot.upcoming)
+            .filter(|m| m.starts_at.is_some() && follow.follows(m))
+            .filter(|m| {
+                let new = !seen.contains(&m.match_id);
+                seen.push(m.match_id.clone());
+                new
+            })
+            .cloned()
+            .collect()
+    }
+
+    // Record the current start times, bumping the sequence of rescheduled matches
+    pub fn revise(&self, matches: &[MatchSegment], now: DateTime<Utc>) -> HashMap<String, CalendarEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut changed = false;
+        for segment in matches {
+            let Some(starts_at) = segment.starts_at else {
+                continue;
+            };
+            match entries.get_mut(&segment.match_id) {
+                Some(entry) if entry.starts_at == starts_at => {}
+                Some(entry) => {
+                    entry.starts_at = starts_at;
+                    entry.sequence += 1;
+                    changed = true;
+                }
+                None => {
+                    entries.insert(segment.match_id.clone(), CalendarEntry { starts_at, sequence: 0 });
+                    changed = true;
+                }
+            }
+        }
+
+        let cutoff = (now - Duration::days(ENTRY_RETENTION_DAYS)).timestamp();
+        let before = entries.len();
+        entries.retain(|_, entry| entry.starts_at >= cutoff);
+        if changed || entries.len() != before {
+            self.save(&entries);
+        }
+        entries.clone()
+    }
+
+    // The whole feed as an .ics document
+    pub fn render(&self, snapshot: &MatchSnapshot, now: DateTime<Utc>) -> String {
+        let matches = self.followed_matches(snapshot);
+        let entries = self.revise(&matches, now);
+        let (_, calendar) = self.settings.lock().unwrap().clone();
+        render_calendar(&matches, &entries, &calendar, now)
+    }
+}
+
+pub fn event_uid(match_id: &str) -> String {
+    format!("{}@{}", match_id, UID_DOMAIN)
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Escape a TEXT value (RFC 5545 section 3.3.11)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Fold a content line at 75 octets without splitting a UTF-8 character
+fn fold_line(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+pub fn render_calendar(
+    matches: &[MatchSegment],
+    entries: &HashMap<String, CalendarEntry>,
+    settings: &CalendarSettings,
+    now: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Valorant matches".to_string(),
+    ];
+
+    for segment in matches {
+        let Some(start) = segment.starts_at.and_then(|t| DateTime::from_timestamp(t, 0)) else {
+            continue;
+        };
+        let end = start + Duration::minutes(settings.match_duration_minutes.max(1) as i64);
+        let summary = format!("{} vs {}", segment.team1, segment.team2);
+        let description = [segment.match_event.as_str(), segment.match_series.as_str()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let sequence = entries.get(&segment.match_id).map_or(0, |e| e.sequence);
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event_uid(&segment.match_id)));
+        lines.push(format!("DTSTAMP:{}", format_utc(now)));
+        lines.push(format!("DTSTART:{}", format_utc(start)));
+        lines.push(format!("DTEND:{}", format_utc(end)));
+        lines.push(format!("SEQUENCE:{}", sequence));
+        lines.push(format!("SUMMARY:{}", escape_text(&summary)));
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        }
+        if !segment.match_page.is_empty() {
+            lines.push(format!("URL:{}", segment.match_page));
+        }
+        for minutes in &settings.alarm_minutes {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape_text(&summary)));
+            lines.push(format!("TRIGGER:-PT{}M", minutes));
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        fold_line(line, &mut out);
+    }
+    out
+}
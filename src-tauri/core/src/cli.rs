@@ -0,0 +1,358 @@
+use crate::calendar::CalendarFeed;
+use crate::poller::{poll_once, run_poller};
+use crate::rankings::{RankingCache, RANKINGS_FILE};
+use crate::schedule::with_start_times;
+use crate::settings::{cli_data_path, cli_settings_path, SettingsState};
+use crate::snapshot::{MatchSnapshot, MatchStore};
+use crate::source::{create_source, MatchSource, SourceOptions};
+use crate::statusline::{self, StatusProtocol, DEFAULT_STATUS_FORMAT};
+use crate::template::{Template, TemplateContext};
+use crate::{MatchSegment, PolymarketOdds};
+use std::collections::HashMap;
+use tokio::time::Duration;
+
+const USAGE: &str = "Usage: valo [COMMAND] [OPTIONS]
+       tauri_app --cli [COMMAND] [OPTIONS]
+
+Commands:
+  live                 Print live matches once (default)
+  watch                Print live matches on every refresh
+  odds <TEAM1> <TEAM2> Print Polymarket odds for a match
+  calendar             Print followed upcoming matches as an iCalendar file
+  status <PROTOCOL>    Stream the featured match for a status bar:
+                       waybar, i3bar or plain (polybar and others)
+
+Options:
+  --format <FORMAT>    text, table or json (default: text)
+  --interval <SECS>    Refresh interval for watch and status (default: 30)
+  --template <STRING>  Status text (default: {team1} {score1}-{score2} {team2} [{map}])
+  --odds               Include Polymarket odds for each match
+  --record <FILE>      Save raw API responses to a session file
+  --replay <FILE>      Read matches and odds from a recorded session
+  --speed <FACTOR>     Replay speed (default: 1, real time)
+  -h, --help           Show this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Table,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Live,
+    Watch,
+    Odds { team1: String, team2: String },
+    Calendar,
+    Status(StatusProtocol),
+    Help,
+}
+
+#[derive(Debug)]
+struct CliOptions {
+    command: Command,
+    format: OutputFormat,
+    interval: Duration,
+    with_odds: bool,
+    template: Option<String>,
+    source: SourceOptions,
+}
+
+fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut options = CliOptions {
+        command: Command::Live,
+        format: OutputFormat::Text,
+        interval: Duration::from_secs(30),
+        with_odds: false,
+        template: None,
+        source: SourceOptions::from_env(),
+    };
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => options.command = Command::Help,
+            "--odds" => options.with_odds = true,
+            "--format" => {
+                options.format = match args.next().map(String::as_str) {
+                    Some("text") => OutputFormat::Text,
+                    Some("table") => OutputFormat::Table,
+                    Some("json") => OutputFormat::Json,
+                    other => return Err(format!("Unknown format: {}", other.unwrap_or(""))),
+                }
+            }
+            "--template" => {
+                options.template = Some(args.next().ok_or("--template expects a format string")?.clone());
+            }
+            "--record" => {
+                options.source.record = Some(args.next().ok_or("--record expects a file")?.into());
+            }
+            "--replay" => {
+                options.source.replay = Some(args.next().ok_or("--replay expects a file")?.into());
+            }
+            "--speed" => {
+                options.source.speed = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|speed: &f64| *speed > 0.0)
+                    .ok_or("--speed expects a positive number")?;
+            }
+            "--interval" => {
+                let secs: u64 = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|secs| *secs > 0)
+                    .ok_or("--interval expects a number of seconds")?;
+                options.interval = Duration::from_secs(secs);
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            value => positional.push(value.to_string()),
+        }
+    }
+
+    if options.command == Command::Help {
+        return Ok(options);
+    }
+    options.command = match positional.first().map(String::as_str) {
+        None | Some("live") => Command::Live,
+        Some("watch") => Command::Watch,
+        Some("odds") => match (positional.get(1), positional.get(2)) {
+            (Some(team1), Some(team2)) => Command::Odds {
+                team1: team1.clone(),
+                team2: team2.clone(),
+            },
+            _ => return Err("odds expects two team names".to_string()),
+        },
+        Some("calendar") => Command::Calendar,
+        Some("status") => match positional.get(1).and_then(|p| StatusProtocol::parse(p)) {
+            Some(protocol) => Command::Status(protocol),
+            None => return Err("status expects waybar, i3bar or plain".to_string()),
+        },
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+    };
+    Ok(options)
+}
+
+fn format_odds(odds: Option<&PolymarketOdds>) -> String {
+    let price = |p: Option<f64>| p.map(|p| format!("{:.0}%", p * 100.0)).unwrap_or_else(|| "-".to_string());
+    match odds {
+        Some(odds) => format!("{} / {}", price(odds.team1_odds), price(odds.team2_odds)),
+        None => "-".to_string(),
+    }
+}
+
+fn render_table(snapshot: &MatchSnapshot, with_odds: bool) -> String {
+    let mut rows = vec![vec![
+        "MATCH".to_string(),
+        "SCORE".to_string(),
+        "MAP".to_string(),
+        "EVENT".to_string(),
+    ]];
+    if with_odds {
+        rows[0].push("ODDS".to_string());
+    }
+    for segment in &snapshot.matches {
+        let mut row = vec![
+            format!("{} vs {}", segment.team1, segment.team2),
+            format!("{} - {}", segment.score1, segment.score2),
+            segment.current_map.clone(),
+            segment.match_event.clone(),
+        ];
+        if with_odds {
+            row.push(format_odds(snapshot.odds.get(&segment.match_id)));
+        }
+        rows.push(row);
+    }
+
+    let columns = rows[0].len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0))
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render(
+    snapshot: &MatchSnapshot,
+    format: OutputFormat,
+    with_odds: bool,
+    template: &Template,
+) -> String {
+    match format {
+        OutputFormat::Json => {
+            let value = if with_odds {
+                serde_json::json!(snapshot)
+            } else {
+                serde_json::json!(snapshot.matches)
+            };
+            value.to_string()
+        }
+        OutputFormat::Table => render_table(snapshot, with_odds),
+        OutputFormat::Text => {
+            if snapshot.matches.is_empty() {
+                return "No live matches".to_string();
+            }
+            snapshot
+                .matches
+                .iter()
+                .map(|segment| {
+                    let odds = snapshot.odds.get(&segment.match_id);
+                    let text = template.render(&TemplateContext::new(segment, odds));
+                    if with_odds {
+                        format!("{} | {}", text, format_odds(snapshot.odds.get(&segment.match_id)))
+                    } else {
+                        text
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+async fn run_command(
+    options: CliOptions,
+    source: &dyn MatchSource,
+    settings: &SettingsState,
+) -> Result<(), String> {
+    let templates = settings.templates();
+    // Ranks come from the rankings the app has cached; the CLI doesn't fetch them
+    let rankings = RankingCache::load(cli_data_path(RANKINGS_FILE));
+    let prepare = |matches: Vec<MatchSegment>| {
+        rankings.annotate(with_start_times(matches, settings.display_zone(), chrono::Utc::now()))
+    };
+    match options.command {
+        Command::Help => println!("{}", USAGE),
+        Command::Live => {
+            let snapshot = if options.with_odds {
+                let store = MatchStore::new();
+                poll_once(&store, source, prepare).await.map_err(|e| e.to_string())?;
+                store.snapshot()
+            } else {
+                MatchSnapshot {
+                    updated_at: chrono::Utc::now().timestamp(),
+                    matches: prepare(source.live_matches().await.map_err(|e| e.to_string())?),
+                    odds: HashMap::new(),
+                    upcoming: Vec::new(),
+                    offline_since: None,
+                }
+            };
+            println!(
+                "{}",
+                render(&snapshot, options.format, options.with_odds, &templates.cli)
+            );
+        }
+        Command::Watch => {
+            // Same poller as the tray; odds are always fetched so they can be shown
+            let store = MatchStore::new();
+            run_poller(&store, source, options.interval, prepare, |snapshot| {
+                if options.format != OutputFormat::Json {
+                    println!("--- {} ---", chrono::Local::now().format("%H:%M:%S"));
+                }
+                println!(
+                    "{}",
+                    render(snapshot, options.format, options.with_odds, &templates.cli)
+                );
+            })
+            .await;
+        }
+        Command::Status(protocol) => {
+            let format = options.template.as_deref().unwrap_or(DEFAULT_STATUS_FORMAT);
+            let template =
+                Template::parse(format).map_err(|e| format!("Invalid --template: {}", e))?;
+            if protocol == StatusProtocol::I3bar {
+                for line in statusline::i3bar_header() {
+                    println!("{}", line);
+                }
+            }
+            let store = MatchStore::new();
+            run_poller(&store, source, options.interval, prepare, |snapshot| {
+                println!(
+                    "{}",
+                    statusline::render_status(protocol, &template, &templates.cli, &snapshot.matches)
+                );
+            })
+            .await;
+        }
+        Command::Odds { team1, team2 } => {
+            let odds = source
+                .odds(&team1, &team2, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            match options.format {
+                OutputFormat::Json => println!("{}", serde_json::json!(odds)),
+                _ => {
+                    println!("{} vs {}: {}", team1, team2, format_odds(Some(&odds)));
+                    if let Some(url) = &odds.market_url {
+                        println!("{}", url);
+                    }
+                }
+            }
+        }
+        Command::Calendar => {
+            // Sequence numbers are only tracked by the app's feed; a one-off export starts at 0
+            let settings = settings.get();
+            let feed = CalendarFeed::load(None, settings.follow, settings.calendar);
+            let snapshot = MatchSnapshot {
+                updated_at: chrono::Utc::now().timestamp(),
+                matches: source.live_matches().await.map_err(|e| e.to_string())?,
+                odds: HashMap::new(),
+                upcoming: source.upcoming_matches().await.map_err(|e| e.to_string())?,
+                offline_since: None,
+            };
+            print!("{}", feed.render(&snapshot, chrono::Utc::now()));
+        }
+    }
+    Ok(())
+}
+
+// Entry point for `valo` and `tauri_app --cli`; returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Error starting runtime: {}", e);
+            return 1;
+        }
+    };
+
+    let source = match create_source(&options.source) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    };
+
+    // Same settings file as the app; template errors are printed while loading
+    let settings = SettingsState::load_from(cli_settings_path());
+
+    match runtime.block_on(run_command(options, source.as_ref(), &settings)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
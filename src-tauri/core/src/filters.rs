@@ -0,0 +1,235 @@
+// Rules deciding which matches reach the tray and window.
+//
+// A match is shown when, for every field that has include rules, it matches at least
+// one of them, and it matches no exclude rule. Patterns are case-insensitive; without
+// a `*` they match any part of the value, with one they must match all of it
+// ("vct*americas*").
+use crate::settings::FilterSettings;
+use crate::snapshot::MatchSnapshot;
+use crate::MatchSegment;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    Include,
+    Exclude,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterField {
+    Event,
+    Series,
+    // Either team
+    Team,
+    // americas, emea, pacific, china or international (see `match_region`)
+    Region,
+    // vct, challengers, game_changers or other (see `match_tier`)
+    Tier,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FilterRule {
+    pub action: FilterAction,
+    pub field: FilterField,
+    pub pattern: String,
+}
+
+impl FilterRule {
+    fn new(action: FilterAction, field: FilterField, pattern: &str) -> Self {
+        FilterRule {
+            action,
+            field,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    fn matches(&self, segment: &MatchSegment) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        let values = match self.field {
+            FilterField::Event => vec![segment.match_event.clone()],
+            FilterField::Series => vec![segment.match_series.clone()],
+            FilterField::Team => vec![segment.team1.clone(), segment.team2.clone()],
+            FilterField::Region => match_region(segment).map(str::to_string).into_iter().collect(),
+            FilterField::Tier => vec![match_tier(segment).to_string()],
+        };
+        values
+            .iter()
+            .any(|value| pattern_matches(&pattern, &value.to_lowercase()))
+    }
+}
+
+// `pattern` is already lowercase
+fn pattern_matches(pattern: &str, value: &str) -> bool {
+    if !pattern.contains('*') {
+        return value.contains(pattern);
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if value.len() < first.len() + last.len() || !value.starts_with(first) || !value.ends_with(last) {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+// Region from the event name; `None` when it can't be told
+pub fn match_region(segment: &MatchSegment) -> Option<&'static str> {
+    const REGIONS: &[(&str, &[&str])] = &[
+        ("china", &["china"]),
+        ("americas", &["americas", "north america", "brazil", "latam", "latin america"]),
+        (
+            "emea",
+            &["emea", "europe", "dach", "france", "spain", "italy", "türkiye", "turkey", "mena", "polaris"],
+        ),
+        (
+            "pacific",
+            &["pacific", "korea", "japan", "south asia", "southeast asia", "oceania", "philippines", "indonesia", "vietnam", "thailand", "malaysia"],
+        ),
+        ("international", &["masters", "champions"]),
+    ];
+    let event = segment.match_event.to_lowercase();
+    REGIONS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| event.contains(k)))
+        .map(|(region, _)| *region)
+}
+
+// Competitive tier from the event name
+pub fn match_tier(segment: &MatchSegment) -> &'static str {
+    let event = segment.match_event.to_lowercase();
+    if event.contains("game changers") {
+        "game_changers"
+    } else if event.contains("challengers") || event.contains("ascension") {
+        "challengers"
+    } else if event.starts_with("vct")
+        || event.contains("champions tour")
+        || event.contains("masters")
+        || event.contains("champions")
+    {
+        "vct"
+    } else {
+        "other"
+    }
+}
+
+// Built-in presets: id, label and rules
+#[derive(Serialize)]
+pub struct FilterPreset {
+    pub id: &'static str,
+    pub label: &'static str,
+    #[serde(skip)]
+    rules: &'static [(FilterField, &'static str)],
+}
+
+pub const PRESETS: &[FilterPreset] = &[
+    FilterPreset {
+        id: "vct",
+        label: "VCT only",
+        rules: &[(FilterField::Tier, "vct")],
+    },
+    FilterPreset {
+        id: "vct_americas",
+        label: "VCT Americas",
+        rules: &[(FilterField::Tier, "vct"), (FilterField::Region, "americas")],
+    },
+    FilterPreset {
+        id: "vct_emea",
+        label: "VCT EMEA",
+        rules: &[(FilterField::Tier, "vct"), (FilterField::Region, "emea")],
+    },
+    FilterPreset {
+        id: "vct_pacific",
+        label: "VCT Pacific",
+        rules: &[(FilterField::Tier, "vct"), (FilterField::Region, "pacific")],
+    },
+    FilterPreset {
+        id: "challengers_emea",
+        label: "Challengers EMEA",
+        rules: &[(FilterField::Tier, "challengers"), (FilterField::Region, "emea")],
+    },
+    FilterPreset {
+        id: "challengers_americas",
+        label: "Challengers Americas",
+        rules: &[(FilterField::Tier, "challengers"), (FilterField::Region, "americas")],
+    },
+    FilterPreset {
+        id: "game_changers",
+        label: "Game Changers",
+        rules: &[(FilterField::Tier, "game_changers")],
+    },
+];
+
+pub fn find_preset(id: &str) -> Option<&'static FilterPreset> {
+    PRESETS.iter().find(|preset| preset.id == id)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MatchFilter {
+    rules: Vec<FilterRule>,
+}
+
+impl MatchFilter {
+    pub fn compile(settings: &FilterSettings) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        if let Some(id) = settings.preset.as_deref().filter(|id| !id.is_empty()) {
+            let preset = find_preset(id).ok_or_else(|| format!("Unknown filter preset '{}'", id))?;
+            rules.extend(
+                preset
+                    .rules
+                    .iter()
+                    .map(|(field, pattern)| FilterRule::new(FilterAction::Include, *field, pattern)),
+            );
+        }
+        rules.extend(settings.rules.iter().filter(|r| !r.pattern.trim().is_empty()).cloned());
+        Ok(MatchFilter { rules })
+    }
+
+    pub fn allows(&self, segment: &MatchSegment) -> bool {
+        let excluded = self
+            .rules
+            .iter()
+            .any(|r| r.action == FilterAction::Exclude && r.matches(segment));
+        if excluded {
+            return false;
+        }
+        let includes = |field| {
+            self.rules
+                .iter()
+                .filter(move |r| r.action == FilterAction::Include && r.field == field)
+        };
+        [
+            FilterField::Event,
+            FilterField::Series,
+            FilterField::Team,
+            FilterField::Region,
+            FilterField::Tier,
+        ]
+        .into_iter()
+        .all(|field| {
+            let mut rules = includes(field).peekable();
+            rules.peek().is_none() || rules.any(|r| r.matches(segment))
+        })
+    }
+
+    pub fn filter(&self, segments: Vec<MatchSegment>) -> Vec<MatchSegment> {
+        segments.into_iter().filter(|m| self.allows(m)).collect()
+    }
+
+    // Snapshot with only the allowed matches and their odds
+    pub fn apply(&self, snapshot: &MatchSnapshot) -> MatchSnapshot {
+        let mut filtered = snapshot.clone();
+        filtered.matches.retain(|m| self.allows(m));
+        filtered.upcoming.retain(|m| self.allows(m));
+        let kept: Vec<&str> = filtered.matches.iter().map(|m| m.match_id.as_str()).collect();
+        filtered.odds.retain(|id, _| kept.contains(&id.as_str()));
+        filtered
+    }
+}
@@ -0,0 +1,156 @@
+// Team countries from the API's flag codes.
+//
+// vlrggapi sends the CSS class vlr.gg uses for a team's flag, e.g. "flag_us". The
+// code is an ISO 3166-1 alpha-2 code in lower case, except "uk" for Great Britain,
+// "eu" for teams listed as European and "un" for international or unknown teams,
+// which has no country. Emoji flags are the code's two regional indicator symbols.
+use crate::models::Country;
+
+const COUNTRY_NAMES: &[(&str, &str)] = &[
+    ("AE", "United Arab Emirates"),
+    ("AR", "Argentina"),
+    ("AT", "Austria"),
+    ("AU", "Australia"),
+    ("AZ", "Azerbaijan"),
+    ("BA", "Bosnia and Herzegovina"),
+    ("BE", "Belgium"),
+    ("BG", "Bulgaria"),
+    ("BH", "Bahrain"),
+    ("BO", "Bolivia"),
+    ("BR", "Brazil"),
+    ("BY", "Belarus"),
+    ("CA", "Canada"),
+    ("CH", "Switzerland"),
+    ("CL", "Chile"),
+    ("CN", "China"),
+    ("CO", "Colombia"),
+    ("CR", "Costa Rica"),
+    ("CY", "Cyprus"),
+    ("CZ", "Czechia"),
+    ("DE", "Germany"),
+    ("DK", "Denmark"),
+    ("DO", "Dominican Republic"),
+    ("DZ", "Algeria"),
+    ("EC", "Ecuador"),
+    ("EE", "Estonia"),
+    ("EG", "Egypt"),
+    ("ES", "Spain"),
+    ("EU", "Europe"),
+    ("FI", "Finland"),
+    ("FR", "France"),
+    ("GB", "United Kingdom"),
+    ("GE", "Georgia"),
+    ("GR", "Greece"),
+    ("GT", "Guatemala"),
+    ("HK", "Hong Kong"),
+    ("HN", "Honduras"),
+    ("HR", "Croatia"),
+    ("HU", "Hungary"),
+    ("ID", "Indonesia"),
+    ("IE", "Ireland"),
+    ("IL", "Israel"),
+    ("IN", "India"),
+    ("IQ", "Iraq"),
+    ("IR", "Iran"),
+    ("IS", "Iceland"),
+    ("IT", "Italy"),
+    ("JO", "Jordan"),
+    ("JP", "Japan"),
+    ("KH", "Cambodia"),
+    ("KR", "South Korea"),
+    ("KW", "Kuwait"),
+    ("KZ", "Kazakhstan"),
+    ("LB", "Lebanon"),
+    ("LT", "Lithuania"),
+    ("LU", "Luxembourg"),
+    ("LV", "Latvia"),
+    ("MA", "Morocco"),
+    ("MD", "Moldova"),
+    ("ME", "Montenegro"),
+    ("MK", "North Macedonia"),
+    ("MN", "Mongolia"),
+    ("MO", "Macau"),
+    ("MT", "Malta"),
+    ("MX", "Mexico"),
+    ("MY", "Malaysia"),
+    ("NI", "Nicaragua"),
+    ("NL", "Netherlands"),
+    ("NO", "Norway"),
+    ("NZ", "New Zealand"),
+    ("OM", "Oman"),
+    ("PA", "Panama"),
+    ("PE", "Peru"),
+    ("PH", "Philippines"),
+    ("PK", "Pakistan"),
+    ("PL", "Poland"),
+    ("PR", "Puerto Rico"),
+    ("PS", "Palestine"),
+    ("PT", "Portugal"),
+    ("PY", "Paraguay"),
+    ("QA", "Qatar"),
+    ("RO", "Romania"),
+    ("RS", "Serbia"),
+    ("RU", "Russia"),
+    ("SA", "Saudi Arabia"),
+    ("SE", "Sweden"),
+    ("SG", "Singapore"),
+    ("SI", "Slovenia"),
+    ("SK", "Slovakia"),
+    ("SV", "El Salvador"),
+    ("SY", "Syria"),
+    ("TH", "Thailand"),
+    ("TN", "Tunisia"),
+    ("TR", "Türkiye"),
+    ("TW", "Taiwan"),
+    ("UA", "Ukraine"),
+    ("US", "United States"),
+    ("UY", "Uruguay"),
+    ("UZ", "Uzbekistan"),
+    ("VE", "Venezuela"),
+    ("VN", "Vietnam"),
+    ("XK", "Kosovo"),
+    ("ZA", "South Africa"),
+];
+
+// ISO code of a flag class such as "flag_us" (also accepts "mod-us" or a bare "us")
+pub fn flag_code(flag: &str) -> Option<String> {
+    let flag = flag.trim().to_lowercase();
+    let code = flag
+        .strip_prefix("flag_")
+        .or_else(|| flag.strip_prefix("mod-"))
+        .unwrap_or(&flag);
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    match code {
+        "un" => None,
+        "uk" => Some("GB".to_string()),
+        code => Some(code.to_uppercase()),
+    }
+}
+
+// "US" -> "🇺🇸"
+pub fn flag_emoji(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphabetic)
+        .filter_map(|c| char::from_u32(0x1F1E6 + (c.to_ascii_uppercase() as u32 - 'A' as u32)))
+        .collect()
+}
+
+// English name of a country code; codes not in the table are returned as they are
+pub fn country_name(code: &str) -> String {
+    COUNTRY_NAMES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| code.to_uppercase())
+}
+
+pub fn country_from_flag(flag: &str) -> Option<Country> {
+    let code = flag_code(flag)?;
+    Some(Country {
+        name: country_name(&code),
+        emoji: flag_emoji(&code),
+        code,
+    })
+}
@@ -0,0 +1,50 @@
+use crate::models::MatchSegment;
+use crate::snapshot::MatchSnapshot;
+use crate::template::{Template, TemplateContext};
+
+// Tray tooltip for the featured (first) match
+pub fn tray_tooltip(snapshot: &MatchSnapshot, template: &Template) -> String {
+    if snapshot.offline_since.is_some() {
+        return "Valorant: Offline, waiting for a connection".to_string();
+    }
+    match snapshot.matches.first() {
+        Some(first_match) => {
+            let odds = snapshot.odds.get(&first_match.match_id);
+            template.render(&TemplateContext::new(first_match, odds))
+        }
+        None => "Valorant: No live matches".to_string(),
+    }
+}
+
+pub fn format_match_text(segment: &MatchSegment, template: &Template) -> String {
+    template.render(&TemplateContext::new(segment, None))
+}
+
+// Short team tag for compact text (e.g. "Sentinels" -> "SEN", "Team Liquid" -> "TL")
+pub fn team_tag(team: &str) -> String {
+    let known = [
+        ("sentinels", "SEN"),
+        ("100 thieves", "100T"),
+        ("g2 esports", "G2"),
+        ("fnatic", "FNC"),
+        ("team liquid", "TL"),
+        ("paper rex", "PRX"),
+        ("kru esports", "KRU"),
+        ("krü esports", "KRÜ"),
+    ];
+    let team_lower = team.to_lowercase();
+    if let Some((_, tag)) = known.iter().find(|(name, _)| *name == team_lower) {
+        return tag.to_string();
+    }
+
+    let words: Vec<&str> = team.split_whitespace().collect();
+    let tag: String = if words.len() > 1 {
+        // Initials for multi-word names
+        words.iter().filter_map(|w| w.chars().next()).take(4).collect()
+    } else {
+        // Short names are already tags (e.g. "DRX", "LOUD"); longer ones are abbreviated
+        let len = if team.chars().count() <= 4 { 4 } else { 3 };
+        team.chars().filter(|c| c.is_alphanumeric()).take(len).collect()
+    };
+    tag.to_uppercase()
+}
@@ -0,0 +1,421 @@
+use crate::snapshot::{map_rounds, MatchEvent, MatchSnapshot, MatchStore};
+use crate::MatchSegment;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::error::RecvError;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    match_id    TEXT PRIMARY KEY,
+    team1       TEXT NOT NULL,
+    team2       TEXT NOT NULL,
+    event       TEXT NOT NULL,
+    series      TEXT NOT NULL,
+    match_page  TEXT NOT NULL,
+    started_at  INTEGER NOT NULL,
+    updated_at  INTEGER NOT NULL,
+    ended_at    INTEGER,
+    score1      INTEGER NOT NULL,
+    score2      INTEGER NOT NULL,
+    winner      TEXT
+);
+CREATE TABLE IF NOT EXISTS snapshots (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    match_id    TEXT NOT NULL REFERENCES matches(match_id),
+    recorded_at INTEGER NOT NULL,
+    score1      INTEGER NOT NULL,
+    score2      INTEGER NOT NULL,
+    map         TEXT NOT NULL,
+    map_number  TEXT NOT NULL,
+    rounds1     INTEGER NOT NULL,
+    rounds2     INTEGER NOT NULL,
+    team1_odds  REAL,
+    team2_odds  REAL
+);
+CREATE INDEX IF NOT EXISTS snapshots_match ON snapshots(match_id, recorded_at);
+CREATE TABLE IF NOT EXISTS maps (
+    match_id    TEXT NOT NULL REFERENCES matches(match_id),
+    map_index   INTEGER NOT NULL,
+    map         TEXT NOT NULL,
+    winner      TEXT NOT NULL,
+    rounds1     INTEGER NOT NULL,
+    rounds2     INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    PRIMARY KEY (match_id, map_index)
+);
+";
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct HistoryFilter {
+    // Case-insensitive substring of either team name
+    pub team: Option<String>,
+    // Case-insensitive substring of the event name
+    pub event: Option<String>,
+    // Unix timestamps bounding when the match was first seen
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<u32>,
+    pub include_snapshots: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MapResult {
+    pub map: String,
+    pub winner: String,
+    pub rounds1: u32,
+    pub rounds2: u32,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SnapshotRecord {
+    pub recorded_at: i64,
+    pub score1: u32,
+    pub score2: u32,
+    pub map: String,
+    pub map_number: String,
+    pub rounds1: u32,
+    pub rounds2: u32,
+    pub team1_odds: Option<f64>,
+    pub team2_odds: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MatchRecord {
+    pub match_id: String,
+    pub team1: String,
+    pub team2: String,
+    pub event: String,
+    pub series: String,
+    pub match_page: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub score1: u32,
+    pub score2: u32,
+    pub winner: Option<String>,
+    pub maps: Vec<MapResult>,
+    // Last odds recorded while the match was live
+    pub team1_odds: Option<f64>,
+    pub team2_odds: Option<f64>,
+    pub snapshots: Vec<SnapshotRecord>,
+}
+
+fn parse_score(value: &str) -> u32 {
+    value.trim().parse().unwrap_or(0)
+}
+
+// LIKE pattern matching `value` anywhere, with its own wildcards taken literally
+fn like_pattern(value: &str) -> String {
+    let escaped = value
+        .trim()
+        .to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn event_match_id(event: &MatchEvent) -> Option<&str> {
+    match event {
+        MatchEvent::MatchStarted { match_id, .. }
+        | MatchEvent::MapStarted { match_id, .. }
+        | MatchEvent::RoundsChanged { match_id, .. }
+        | MatchEvent::MapWon { match_id, .. }
+        | MatchEvent::MatchEnded { match_id, .. }
+        | MatchEvent::OddsChanged { match_id, .. } => Some(match_id),
+        MatchEvent::Offline { .. } | MatchEvent::Online => None,
+    }
+}
+
+// Matches, their snapshots and map results, stored in SQLite
+pub struct HistoryDb {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(HistoryDb {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    // A match seen live again after it ended (a pause or a gap in the feed) is
+    // ongoing until it ends again
+    fn upsert_match(conn: &Connection, segment: &MatchSegment, now: i64) -> rusqlite::Result<()> {
+        conn.execute(
+            "INSERT INTO matches (match_id, team1, team2, event, series, match_page,
+                                  started_at, updated_at, score1, score2)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8, ?9)
+             ON CONFLICT(match_id) DO UPDATE SET
+                 updated_at = excluded.updated_at,
+                 score1 = excluded.score1,
+                 score2 = excluded.score2,
+                 ended_at = NULL,
+                 winner = NULL",
+            params![
+                segment.match_id,
+                segment.team1,
+                segment.team2,
+                segment.match_event,
+                segment.match_series,
+                segment.match_page,
+                now,
+                parse_score(&segment.score1),
+                parse_score(&segment.score2),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_snapshot(
+        conn: &Connection,
+        segment: &MatchSegment,
+        snapshot: &MatchSnapshot,
+        now: i64,
+    ) -> rusqlite::Result<()> {
+        let (rounds1, rounds2) = map_rounds(segment);
+        let odds = snapshot.odds.get(&segment.match_id);
+        let row = SnapshotRecord {
+            recorded_at: now,
+            score1: parse_score(&segment.score1),
+            score2: parse_score(&segment.score2),
+            map: segment.current_map.clone(),
+            map_number: segment.map_number.clone(),
+            rounds1,
+            rounds2,
+            team1_odds: odds.and_then(|o| o.team1_odds),
+            team2_odds: odds.and_then(|o| o.team2_odds),
+        };
+
+        // One poll can produce several events for a match; keep a single row per state
+        let latest = Self::snapshots(conn, &segment.match_id, true)?;
+        if latest
+            .first()
+            .is_some_and(|last| SnapshotRecord { recorded_at: now, ..last.clone() } == row)
+        {
+            return Ok(());
+        }
+
+        conn.execute(
+            "INSERT INTO snapshots (match_id, recorded_at, score1, score2, map, map_number,
+                                    rounds1, rounds2, team1_odds, team2_odds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                segment.match_id,
+                row.recorded_at,
+                row.score1,
+                row.score2,
+                row.map,
+                row.map_number,
+                row.rounds1,
+                row.rounds2,
+                row.team1_odds,
+                row.team2_odds,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Record what a diff event says about a match, using the snapshot it came from
+    pub fn record_event(&self, event: &MatchEvent, snapshot: &MatchSnapshot) -> rusqlite::Result<()> {
+        // Connectivity changes aren't about any one match
+        let Some(match_id) = event_match_id(event) else {
+            return Ok(());
+        };
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        if let MatchEvent::MatchEnded { last_seen, .. } = event {
+            Self::upsert_match(&conn, last_seen, now)?;
+            let (score1, score2) = (parse_score(&last_seen.score1), parse_score(&last_seen.score2));
+            let winner = match score1.cmp(&score2) {
+                std::cmp::Ordering::Greater => Some(&last_seen.team1),
+                std::cmp::Ordering::Less => Some(&last_seen.team2),
+                std::cmp::Ordering::Equal => None,
+            };
+            conn.execute(
+                "UPDATE matches SET ended_at = ?2, winner = ?3 WHERE match_id = ?1",
+                params![match_id, now, winner],
+            )?;
+            return Ok(());
+        }
+
+        let Some(segment) = snapshot.matches.iter().find(|m| m.match_id == match_id) else {
+            return Ok(());
+        };
+        Self::upsert_match(&conn, segment, now)?;
+
+        if let MatchEvent::MapWon { team, map, score1, score2, .. } = event {
+            // Final rounds of the map are the last ones recorded while it was played
+            let rounds: Option<(u32, u32)> = conn
+                .query_row(
+                    "SELECT rounds1, rounds2 FROM snapshots
+                     WHERE match_id = ?1 AND map = ?2
+                     ORDER BY recorded_at DESC, id DESC LIMIT 1",
+                    params![match_id, map],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            let (rounds1, rounds2) = rounds.unwrap_or((0, 0));
+            conn.execute(
+                "INSERT OR REPLACE INTO maps (match_id, map_index, map, winner, rounds1, rounds2, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    match_id,
+                    parse_score(score1) + parse_score(score2),
+                    map,
+                    team,
+                    rounds1,
+                    rounds2,
+                    now,
+                ],
+            )?;
+        }
+
+        Self::insert_snapshot(&conn, segment, snapshot, now)
+    }
+
+    fn snapshots(conn: &Connection, match_id: &str, latest_only: bool) -> rusqlite::Result<Vec<SnapshotRecord>> {
+        let sql = if latest_only {
+            "SELECT recorded_at, score1, score2, map, map_number, rounds1, rounds2, team1_odds, team2_odds
+             FROM snapshots WHERE match_id = ?1 ORDER BY recorded_at DESC, id DESC LIMIT 1"
+        } else {
+            "SELECT recorded_at, score1, score2, map, map_number, rounds1, rounds2, team1_odds, team2_odds
+             FROM snapshots WHERE match_id = ?1 ORDER BY recorded_at, id"
+        };
+        let mut statement = conn.prepare_cached(sql)?;
+        let rows = statement.query_map(params![match_id], |row| {
+            Ok(SnapshotRecord {
+                recorded_at: row.get(0)?,
+                score1: row.get(1)?,
+                score2: row.get(2)?,
+                map: row.get(3)?,
+                map_number: row.get(4)?,
+                rounds1: row.get(5)?,
+                rounds2: row.get(6)?,
+                team1_odds: row.get(7)?,
+                team2_odds: row.get(8)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    fn maps(conn: &Connection, match_id: &str) -> rusqlite::Result<Vec<MapResult>> {
+        let mut statement = conn.prepare_cached(
+            "SELECT map, winner, rounds1, rounds2 FROM maps WHERE match_id = ?1 ORDER BY map_index",
+        )?;
+        let rows = statement.query_map(params![match_id], |row| {
+            Ok(MapResult {
+                map: row.get(0)?,
+                winner: row.get(1)?,
+                rounds1: row.get(2)?,
+                rounds2: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Matches matching the filter, most recent first
+    pub fn query(&self, filter: &HistoryFilter) -> rusqlite::Result<Vec<MatchRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let like = |value: &Option<String>| {
+            value
+                .as_ref()
+                .filter(|v| !v.trim().is_empty())
+                .map(|v| like_pattern(v))
+        };
+
+        let mut statement = conn.prepare(
+            "SELECT match_id, team1, team2, event, series, match_page, started_at, ended_at,
+                    score1, score2, winner
+             FROM matches
+             WHERE (?1 IS NULL OR lower(team1) LIKE ?1 ESCAPE '\\' OR lower(team2) LIKE ?1 ESCAPE '\\')
+               AND (?2 IS NULL OR lower(event) LIKE ?2 ESCAPE '\\')
+               AND (?3 IS NULL OR started_at >= ?3)
+               AND (?4 IS NULL OR started_at <= ?4)
+             ORDER BY started_at DESC
+             LIMIT ?5",
+        )?;
+        let rows = statement.query_map(
+            params![
+                like(&filter.team),
+                like(&filter.event),
+                filter.from,
+                filter.to,
+                filter.limit.unwrap_or(100),
+            ],
+            |row| {
+                Ok(MatchRecord {
+                    match_id: row.get(0)?,
+                    team1: row.get(1)?,
+                    team2: row.get(2)?,
+                    event: row.get(3)?,
+                    series: row.get(4)?,
+                    match_page: row.get(5)?,
+                    started_at: row.get(6)?,
+                    ended_at: row.get(7)?,
+                    score1: row.get(8)?,
+                    score2: row.get(9)?,
+                    winner: row.get(10)?,
+                    maps: Vec::new(),
+                    team1_odds: None,
+                    team2_odds: None,
+                    snapshots: Vec::new(),
+                })
+            },
+        )?;
+        let mut records = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for record in &mut records {
+            record.maps = Self::maps(&conn, &record.match_id)?;
+            let (team1_odds, team2_odds) = conn
+                .query_row(
+                    "SELECT team1_odds, team2_odds FROM snapshots
+                     WHERE match_id = ?1 AND (team1_odds IS NOT NULL OR team2_odds IS NOT NULL)
+                     ORDER BY recorded_at DESC, id DESC LIMIT 1",
+                    params![record.match_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .unwrap_or((None, None));
+            record.team1_odds = team1_odds;
+            record.team2_odds = team2_odds;
+            if filter.include_snapshots {
+                record.snapshots = Self::snapshots(&conn, &record.match_id, false)?;
+            }
+        }
+        Ok(records)
+    }
+}
+
+// Record every match event into the history database
+pub async fn run_history(store: Arc<MatchStore>, db: Arc<HistoryDb>) {
+    let mut events = store.subscribe();
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Err(e) = db.record_event(&event, &store.snapshot()) {
+                    eprintln!("Error recording match history: {}", e);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                eprintln!("Match history skipped {} events", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Live Valorant match data shared by the menubar app, the headless CLI and other tools:
+// API clients and models, odds, formatting templates, the poller and snapshot diffing,
+// match history, team ratings, rankings, player stats and profiles, news, cached logos,
+// webhooks and the local server.
+pub mod api;
+pub mod calendar;
+pub mod cli;
+pub mod filters;
+pub mod flags;
+pub mod format;
+pub mod history;
+pub mod logos;
+pub mod models;
+pub mod news;
+pub mod persist;
+pub mod poller;
+pub mod player_stats;
+pub mod probability;
+pub mod rankings;
+pub mod ratings;
+pub mod reminders;
+pub mod schedule;
+pub mod server;
+pub mod settings;
+pub mod snapshot;
+pub mod source;
+pub mod statusline;
+pub mod teams;
+pub mod template;
+pub mod webhooks;
+
+pub use models::{MatchSegment, PolymarketOdds};
@@ -0,0 +1,301 @@
+// Team logos cached on disk and served to the window through the `logo` URI scheme.
+//
+// Match data points at logos on vlr.gg's CDN. Instead of the webview fetching them on
+// every render, the app hands it `logo://localhost/<team>?src=<url>` URLs; the first
+// request downloads the image and stores it under the SHA-256 of its contents, later
+// ones are read from disk. `index.json` maps each remote URL to its file, so a logo
+// served from several URLs is stored once. Teams without a logo, or whose logo can't
+// be downloaded, get a generated placeholder: their tag on a color picked from the name.
+use crate::format::team_tag;
+use crate::models::MatchSegment;
+use crate::persist::{load_json, save_json};
+use crate::source::SourceResult;
+use crate::teams::resolve_logo_url;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const LOGO_SCHEME: &str = "logo";
+// Anything bigger is not a team logo
+const MAX_LOGO_BYTES: usize = 2 * 1024 * 1024;
+// A stalled CDN gets the placeholder instead of holding up the window
+const LOGO_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const LOGO_TIMEOUT: Duration = Duration::from_secs(15);
+const INDEX_FILE: &str = "index.json";
+// Where vlr.gg serves logos from; anything else in a request is not downloaded, so a
+// page can't make the app fetch local or private addresses
+pub const LOGO_HOSTS: &[&str] = &["vlr.gg", "owcdn.net"];
+
+const PLACEHOLDER_COLORS: &[&str] = &[
+    "#e5484d", "#f76b15", "#ffc53d", "#46a758", "#12a594", "#0090ff", "#3e63dd", "#8e4ec6",
+    "#d6409f", "#978365",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogoAsset {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+// Base of URLs for the scheme; Windows and Android webviews only load custom schemes
+// as http://<scheme>.localhost
+fn scheme_base() -> String {
+    if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost", LOGO_SCHEME)
+    } else {
+        format!("{}://localhost", LOGO_SCHEME)
+    }
+}
+
+// URL the window loads a team's logo from; an empty `logo` gets the placeholder
+pub fn logo_src(logo: &str, team: &str) -> String {
+    let mut src = format!("{}/{}", scheme_base(), urlencoding::encode(team.trim()));
+    let logo = resolve_logo_url(logo);
+    if !logo.is_empty() {
+        src.push_str("?src=");
+        src.push_str(&urlencoding::encode(&logo));
+    }
+    src
+}
+
+// Matches with their logos loaded through the scheme, for the window
+pub fn with_logo_srcs(mut matches: Vec<MatchSegment>) -> Vec<MatchSegment> {
+    for segment in &mut matches {
+        segment.team1_logo = logo_src(&segment.team1_logo, &segment.team1);
+        segment.team2_logo = logo_src(&segment.team2_logo, &segment.team2);
+    }
+    matches
+}
+
+// Team name and logo URL of a scheme request, e.g. "/Sentinels?src=https%3A%2F%2F..."
+pub fn parse_logo_request(path_and_query: &str) -> (String, Option<String>) {
+    let (path, query) = path_and_query.split_once('?').unwrap_or((path_and_query, ""));
+    let decode = |value: &str| urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_default();
+    let team = decode(path.trim_start_matches('/'));
+    let src = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("src="))
+        .map(decode)
+        .filter(|src| !src.is_empty());
+    (team, src)
+}
+
+// Whether `url` is http(s) on one of `hosts` or a subdomain of one
+pub fn is_allowed_logo_url(url: &str, hosts: &[String]) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str().map(str::to_lowercase) else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && hosts
+            .iter()
+            .any(|allowed| host == *allowed || host.ends_with(&format!(".{}", allowed)))
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Square SVG with the team's tag on a color that stays the same for the team
+pub fn placeholder_svg(team: &str) -> String {
+    let tag = team_tag(team.trim());
+    let tag = if tag.is_empty() { "?".to_string() } else { tag };
+    let hash = Sha256::digest(team.trim().to_lowercase().as_bytes());
+    let color = PLACEHOLDER_COLORS[hash[0] as usize % PLACEHOLDER_COLORS.len()];
+    // Keep four-letter tags inside the square
+    let font_size = match tag.chars().count() {
+        0..=2 => 28,
+        3 => 22,
+        _ => 17,
+    };
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" viewBox="0 0 64 64">"#,
+            r#"<rect width="64" height="64" rx="12" fill="{}"/>"#,
+            r#"<text x="32" y="32" dy=".35em" text-anchor="middle" font-family="Inter, Arial, sans-serif" "#,
+            r#"font-size="{}" font-weight="700" fill="white">{}</text></svg>"#
+        ),
+        color,
+        font_size,
+        xml_escape(&tag)
+    )
+}
+
+pub fn placeholder_asset(team: &str) -> LogoAsset {
+    LogoAsset {
+        content_type: "image/svg+xml".to_string(),
+        bytes: placeholder_svg(team).into_bytes(),
+    }
+}
+
+fn extension(content_type: &str) -> Option<&'static str> {
+    match content_type.split(';').next().unwrap_or("").trim() {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/svg+xml" => Some("svg"),
+        "image/webp" => Some("webp"),
+        "image/gif" => Some("gif"),
+        _ => None,
+    }
+}
+
+fn content_type(ext: &str) -> Option<&'static str> {
+    match ext.to_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "svg" => Some("image/svg+xml"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        _ => None,
+    }
+}
+
+// Extension of a file name or URL path
+fn file_extension(name: &str) -> &str {
+    let name = name.split(['?', '#']).next().unwrap_or(name);
+    name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("")
+}
+
+// Downloaded logos and where they are stored. Without a directory nothing is kept and
+// logos are downloaded on every request.
+pub struct LogoCache {
+    dir: Option<PathBuf>,
+    client: reqwest::Client,
+    // Hosts logos are downloaded from, `LOGO_HOSTS` unless changed
+    hosts: Vec<String>,
+    // Remote URL to file name in `dir`
+    index: Mutex<HashMap<String, String>>,
+}
+
+impl LogoCache {
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let index = load_json(dir.as_ref().map(|dir| dir.join(INDEX_FILE)).as_deref());
+        LogoCache {
+            dir,
+            client: reqwest::Client::builder()
+                .connect_timeout(LOGO_CONNECT_TIMEOUT)
+                .timeout(LOGO_TIMEOUT)
+                .build()
+                .expect("HTTP client"),
+            hosts: LOGO_HOSTS.iter().map(|host| host.to_string()).collect(),
+            index: Mutex::new(index),
+        }
+    }
+
+    // Download logos from other hosts than vlr.gg's, e.g. a local mock server
+    pub fn with_hosts(mut self, hosts: &[&str]) -> Self {
+        self.hosts = hosts.iter().map(|host| host.to_lowercase()).collect();
+        self
+    }
+
+    fn save_index(&self, index: &HashMap<String, String>) {
+        let path = self.dir.as_ref().map(|dir| dir.join(INDEX_FILE));
+        save_json(path.as_deref(), index, "logo index");
+    }
+
+    // A logo already on disk
+    pub fn cached(&self, url: &str) -> Option<LogoAsset> {
+        let dir = self.dir.as_ref()?;
+        let file = self.index.lock().unwrap().get(url).cloned()?;
+        let bytes = fs::read(dir.join(&file)).ok()?;
+        Some(LogoAsset {
+            content_type: content_type(file_extension(&file)).unwrap_or("application/octet-stream").to_string(),
+            bytes,
+        })
+    }
+
+    async fn download(&self, url: &str) -> SourceResult<LogoAsset> {
+        let mut response = self
+            .client
+            .get(url)
+            .header("User-Agent", "Valorant-Menubar-App")
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Logo {} returned {}", url, status.as_u16()).into());
+        }
+        let header = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        // Oversized bodies are refused by their announced size, or once reading them
+        // passes the limit, rather than being held in memory whole
+        if response.content_length().is_some_and(|length| length > MAX_LOGO_BYTES as u64) {
+            return Err(format!("Logo {} is over {} bytes", url, MAX_LOGO_BYTES).into());
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() > MAX_LOGO_BYTES {
+                return Err(format!("Logo {} is over {} bytes", url, MAX_LOGO_BYTES).into());
+            }
+        }
+        if bytes.is_empty() {
+            return Err(format!("Logo {} is empty", url).into());
+        }
+        // CDNs don't always send an image content type, so the URL's extension is the
+        // fallback; text is an error page
+        if header.as_deref().is_some_and(|h| h.starts_with("text/")) {
+            return Err(format!("Logo {} is not an image", url).into());
+        }
+        let ext = match header.as_deref().and_then(extension) {
+            Some(ext) => ext,
+            None => file_extension(url),
+        };
+        let content_type = content_type(ext).ok_or_else(|| format!("Logo {} is not an image", url))?;
+        Ok(LogoAsset {
+            content_type: content_type.to_string(),
+            bytes,
+        })
+    }
+
+    // The logo at `url`, from disk or downloaded and stored
+    pub async fn fetch(&self, url: &str) -> SourceResult<LogoAsset> {
+        if let Some(asset) = self.cached(url) {
+            return Ok(asset);
+        }
+        let asset = self.download(url).await?;
+        let Some(dir) = &self.dir else {
+            return Ok(asset);
+        };
+
+        let ext = extension(&asset.content_type).unwrap_or("img");
+        let file = format!("{}.{}", content_hash(&asset.bytes), ext);
+        let path = dir.join(&file);
+        if !path.exists() {
+            let _ = fs::create_dir_all(dir);
+            if let Err(e) = fs::write(&path, &asset.bytes) {
+                eprintln!("Error caching logo {}: {}", url, e);
+                return Ok(asset);
+            }
+        }
+        let mut index = self.index.lock().unwrap();
+        index.insert(url.to_string(), file);
+        self.save_index(&index);
+        Ok(asset)
+    }
+
+    // Answer a `logo` scheme request: the team's logo, or its placeholder when it has
+    // none, it isn't on a logo host or the download fails
+    pub async fn serve(&self, path_and_query: &str) -> LogoAsset {
+        let (team, src) = parse_logo_request(path_and_query);
+        if let Some(src) = src.filter(|src| is_allowed_logo_url(src, &self.hosts)) {
+            match self.fetch(&src).await {
+                Ok(asset) => return asset,
+                Err(e) => eprintln!("Error fetching logo for {}: {}", team, e),
+            }
+        }
+        placeholder_asset(&team)
+    }
+}
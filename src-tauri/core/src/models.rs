@@ -0,0 +1,353 @@
+use crate::flags::country_from_flag;
+use crate::schedule;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct ApiResponse<T = MatchSegment> {
+    pub status: String,
+    pub data: ApiData<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiData<T = MatchSegment> {
+    pub status: u16,
+    pub segments: Vec<T>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MatchSegment {
+    pub team1: String,
+    pub team2: String,
+    // Upcoming matches have no score yet
+    #[serde(default)]
+    pub score1: String,
+    #[serde(default)]
+    pub score2: String,
+    #[serde(default)]
+    pub current_map: String,
+    #[serde(default)]
+    pub match_event: String,
+    #[serde(default)]
+    pub match_series: String,
+    #[serde(default)]
+    pub time_until_match: String,
+    #[serde(default)]
+    pub flag1: String,
+    #[serde(default)]
+    pub flag2: String,
+    #[serde(default)]
+    pub team1_logo: String,
+    #[serde(default)]
+    pub team2_logo: String,
+    #[serde(default)]
+    pub team1_round_ct: String,
+    #[serde(default)]
+    pub team1_round_t: String,
+    #[serde(default)]
+    pub team2_round_ct: String,
+    #[serde(default)]
+    pub team2_round_t: String,
+    #[serde(default)]
+    pub map_number: String,
+    #[serde(default)]
+    pub unix_timestamp: String,
+    #[serde(default)]
+    pub match_page: String,
+    // Stable identifier derived from the match page, filled in after fetching
+    #[serde(default)]
+    pub match_id: String,
+    // Start time as unix seconds, parsed from `unix_timestamp`
+    #[serde(default)]
+    pub starts_at: Option<i64>,
+    // Start time in the configured timezone, e.g. "Tomorrow 01:30 CEST"
+    #[serde(default)]
+    pub start_time: String,
+    // vlr.gg regional ranking of each team, from the cached rankings
+    #[serde(default)]
+    pub rank1: Option<TeamRank>,
+    #[serde(default)]
+    pub rank2: Option<TeamRank>,
+    // Country of each team, parsed from `flag1`/`flag2`
+    #[serde(default)]
+    pub country1: Option<Country>,
+    #[serde(default)]
+    pub country2: Option<Country>,
+}
+
+// A team's country: ISO code (e.g. "US"), English name and emoji flag
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Country {
+    pub code: String,
+    pub name: String,
+    pub emoji: String,
+}
+
+// A team's place in its region's ranking, e.g. #3 in "eu" shown as "#3 EMEA"
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamRank {
+    pub rank: u32,
+    pub region: String,
+    pub region_name: String,
+}
+
+// One team in a regional ranking
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RankedTeam {
+    #[serde(deserialize_with = "number_from_string")]
+    pub rank: u32,
+    pub team: String,
+    #[serde(default)]
+    pub country: String,
+    // e.g. "2d ago"
+    #[serde(default)]
+    pub last_played: String,
+    #[serde(default)]
+    pub last_played_team: String,
+    #[serde(default)]
+    pub last_played_team_logo: String,
+    // Wins and losses, e.g. "12-3"
+    #[serde(default)]
+    pub record: String,
+    // e.g. "$1,234,500"
+    #[serde(default)]
+    pub earnings: String,
+    #[serde(default)]
+    pub logo: String,
+}
+
+// One player's line on a map scoreboard. Stats vlr.gg hasn't filled in yet are `None`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub name: String,
+    // Team tag shown under the name, e.g. "SEN"
+    pub team: String,
+    // Agents played on the map, e.g. ["Jett"]
+    pub agents: Vec<String>,
+    pub rating: Option<f64>,
+    // Average combat score
+    pub acs: Option<u32>,
+    pub kills: Option<u32>,
+    pub deaths: Option<u32>,
+    pub assists: Option<u32>,
+    // Kill, assist, survive or trade rate in percent
+    pub kast: Option<u32>,
+    // Average damage per round
+    pub adr: Option<u32>,
+    pub headshot_percent: Option<u32>,
+    pub first_kills: Option<u32>,
+    pub first_deaths: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamScoreboard {
+    pub team: String,
+    // Rounds won on the map
+    pub score: Option<u32>,
+    pub players: Vec<PlayerStats>,
+}
+
+// Per-player stats for one map of a match, from its vlr.gg match page
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MapScoreboard {
+    pub match_id: String,
+    pub map: String,
+    // 1-based position of the map in the series
+    pub map_number: u32,
+    pub team1: TeamScoreboard,
+    pub team2: TeamScoreboard,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RosterMember {
+    pub alias: String,
+    #[serde(default)]
+    pub real_name: String,
+    // "player", or a staff role such as "head coach"
+    #[serde(default)]
+    pub role: String,
+    // Country code as vlr.gg gives it, e.g. "ca"
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub link: String,
+}
+
+// A team's past or scheduled match from the team endpoint
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamMatch {
+    #[serde(default)]
+    pub match_page: String,
+    pub opponent: String,
+    // Maps won by the team and the opponent, e.g. "2:1"; empty for scheduled matches
+    #[serde(default)]
+    pub score: String,
+    // "win" or "loss"; empty for scheduled matches
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub event: String,
+    #[serde(default)]
+    pub date: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub tag: String,
+    #[serde(default)]
+    pub logo: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub roster: Vec<RosterMember>,
+    #[serde(default)]
+    pub recent_matches: Vec<TeamMatch>,
+    #[serde(default)]
+    pub upcoming_matches: Vec<TeamMatch>,
+    // Results of the latest matches, most recent first, e.g. "WWLW"
+    #[serde(default)]
+    pub form: String,
+}
+
+// A headline from the vlrggapi news endpoint
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct NewsArticle {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    // As vlr.gg shows it, e.g. "April 4, 2025"
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub author: String,
+    // Article path on vlr.gg, e.g. "/451234/sentinels-sign-..."
+    #[serde(default)]
+    pub url_path: String,
+}
+
+// The API sends numbers as strings, sometimes as numbers
+fn number_from_string<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(u32),
+        Text(String),
+    }
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => Ok(n),
+        Value::Text(text) => text
+            .trim()
+            .trim_start_matches('#')
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid number '{}'", text))),
+    }
+}
+
+// Completed match from the results feed
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MatchResult {
+    pub team1: String,
+    pub team2: String,
+    // Maps won by each team
+    pub score1: String,
+    pub score2: String,
+    #[serde(default)]
+    pub flag1: String,
+    #[serde(default)]
+    pub flag2: String,
+    // How long ago the match finished, e.g. "2h 30m ago"
+    #[serde(default)]
+    pub time_completed: String,
+    #[serde(default)]
+    pub round_info: String,
+    #[serde(default)]
+    pub tournament_name: String,
+    #[serde(default)]
+    pub match_page: String,
+    #[serde(default)]
+    pub tournament_icon: String,
+    // Filled in after fetching, like `MatchSegment::match_id`
+    #[serde(default)]
+    pub match_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PolymarketOdds {
+    pub team1_odds: Option<f64>,
+    pub team2_odds: Option<f64>,
+    pub market_url: Option<String>,
+}
+
+// Match id from a vlr.gg match page (e.g. "https://www.vlr.gg/12345/..." -> "12345"),
+// falling back to the team names when the page is missing
+fn page_match_id(match_page: &str, team1: &str, team2: &str) -> String {
+    let page_id = match_page
+        .split('/')
+        .find(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    match page_id {
+        Some(id) => id.to_string(),
+        None => format!("{}-{}", team_to_slug(team1), team_to_slug(team2)),
+    }
+}
+
+pub fn match_id(segment: &MatchSegment) -> String {
+    page_match_id(&segment.match_page, &segment.team1, &segment.team2)
+}
+
+pub fn with_result_ids(mut results: Vec<MatchResult>) -> Vec<MatchResult> {
+    for result in &mut results {
+        result.match_id = page_match_id(&result.match_page, &result.team1, &result.team2);
+    }
+    results
+}
+
+// Fill in the fields computed from the API data. `start_time` depends on the display
+// timezone and is filled in by `schedule::with_start_times`.
+pub fn with_derived_fields(mut segments: Vec<MatchSegment>) -> Vec<MatchSegment> {
+    for segment in &mut segments {
+        segment.match_id = match_id(segment);
+        segment.country1 = country_from_flag(&segment.flag1);
+        segment.country2 = country_from_flag(&segment.flag2);
+        segment.starts_at = schedule::parse_start(&segment.unix_timestamp).map(|t| t.timestamp());
+    }
+    segments
+}
+
+// Convert team name to Polymarket slug format (lowercase, remove spaces, common abbreviations)
+pub fn team_to_slug(team: &str) -> String {
+    let team_lower = team.to_lowercase();
+    // Common team abbreviations mapping
+    let abbreviations: HashMap<&str, &str> = [
+        ("100 thieves", "100t"),
+        ("100t", "100t"),
+        ("mibr", "mibr"),
+        ("nrg", "nrg"),
+        ("g2 esports", "g2"),
+        ("g2", "g2"),
+        ("sentinels", "sentinels"),
+        ("fnatic", "fnatic"),
+        ("team liquid", "tl"),
+        ("paper rex", "prx"),
+        ("loud", "loud"),
+        ("kru esports", "kru"),
+        ("kru", "kru"),
+        ("koi", "koi"),
+        ("drx", "drx"),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    
+    if let Some(abbr) = abbreviations.get(team_lower.as_str()) {
+        return abbr.to_string();
+    }
+    
+    // Otherwise, convert to slug: lowercase, remove spaces and special chars
+    team_lower
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
@@ -0,0 +1,208 @@
+// Esports headlines from the vlrggapi news endpoint.
+//
+// The feed is fetched periodically and cached in news.json so headlines are there at
+// startup and survive a failed fetch. Articles are deduped by their vlr.gg path (or
+// title when there is none), so one that stays on the endpoint for days is only new
+// once. Followed teams are matched against the title and description as whole words.
+use crate::api::VLR_URL;
+use crate::models::NewsArticle;
+use crate::persist::{load_json, save_json};
+use crate::settings::Settings;
+use crate::snapshot::MatchStore;
+use crate::source::{MatchSource, SharedSource, SourceResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration};
+
+// Headlines change a few times a day
+pub const NEWS_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// Articles kept in the cache, newest first
+const ARTICLE_LIMIT: usize = 100;
+// Keys of articles already seen, kept longer than the articles themselves
+const SEEN_LIMIT: usize = 1000;
+
+// An article with the followed teams it mentions
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct NewsItem {
+    #[serde(flatten)]
+    pub article: NewsArticle,
+    pub url: String,
+    pub teams: Vec<String>,
+    // When the app first saw the article, unix seconds
+    pub seen_at: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct CachedArticle {
+    article: NewsArticle,
+    seen_at: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(default)]
+struct NewsState {
+    // Newest first
+    articles: Vec<CachedArticle>,
+    // Oldest first
+    seen: Vec<String>,
+    fetched_at: Option<i64>,
+}
+
+fn article_key(article: &NewsArticle) -> String {
+    let path = article.url_path.trim().trim_matches('/');
+    if path.is_empty() {
+        article.title.trim().to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+pub fn article_url(article: &NewsArticle) -> String {
+    let path = article.url_path.trim();
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}/{}", VLR_URL, path.trim_start_matches('/'))
+    }
+}
+
+// Whether `text` has `team` as a whole word, ignoring case; "G2" is not in "G2A"
+fn mentions(text: &str, team: &str) -> bool {
+    let (text, team) = (text.to_lowercase(), team.trim().to_lowercase());
+    if team.is_empty() {
+        return false;
+    }
+    text.match_indices(&team).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + team.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+// The teams of `teams` an article mentions, in the order given
+pub fn mentioned_teams(article: &NewsArticle, teams: &[String]) -> Vec<String> {
+    let text = format!("{}\n{}", article.title, article.description);
+    teams.iter().filter(|team| mentions(&text, team)).cloned().collect()
+}
+
+// Cached headlines and where they are persisted
+pub struct NewsFeed {
+    path: Option<PathBuf>,
+    state: Mutex<NewsState>,
+}
+
+impl NewsFeed {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let state = load_json(path.as_deref());
+        NewsFeed {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn save(&self, state: &NewsState) {
+        save_json(self.path.as_deref(), state, "news");
+    }
+
+    // Add a fetched feed (newest first) and return the articles not seen before.
+    // The first feed ever recorded only fills the cache: nothing in it counts as new,
+    // so the first start doesn't notify for every headline on the endpoint.
+    pub fn record(&self, articles: &[NewsArticle], now: i64) -> Vec<NewsArticle> {
+        let mut state = self.state.lock().unwrap();
+        let first = state.fetched_at.is_none();
+        state.fetched_at = Some(now);
+
+        let mut added = Vec::new();
+        for article in articles.iter().rev() {
+            let key = article_key(article);
+            if key.is_empty() || state.seen.contains(&key) {
+                continue;
+            }
+            state.seen.push(key);
+            state.articles.insert(
+                0,
+                CachedArticle {
+                    article: article.clone(),
+                    seen_at: now,
+                },
+            );
+            added.push(article.clone());
+        }
+        state.articles.truncate(ARTICLE_LIMIT);
+        let excess = state.seen.len().saturating_sub(SEEN_LIMIT);
+        state.seen.drain(..excess);
+        self.save(&state);
+
+        added.reverse();
+        if first {
+            Vec::new()
+        } else {
+            added
+        }
+    }
+
+    // Fetch the feed and record it; returns the new articles
+    pub async fn refresh(&self, source: &dyn MatchSource, now: i64) -> SourceResult<Vec<NewsArticle>> {
+        let articles = source.news().await?;
+        Ok(self.record(&articles, now))
+    }
+
+    // Cached articles, newest first, each with the followed teams it mentions. With
+    // `followed_only` only articles mentioning one of `teams` are returned.
+    pub fn articles(&self, teams: &[String], followed_only: bool) -> Vec<NewsItem> {
+        let state = self.state.lock().unwrap();
+        state
+            .articles
+            .iter()
+            .map(|cached| NewsItem {
+                url: article_url(&cached.article),
+                teams: mentioned_teams(&cached.article, teams),
+                article: cached.article.clone(),
+                seen_at: cached.seen_at,
+            })
+            .filter(|item| !followed_only || !item.teams.is_empty())
+            .collect()
+    }
+}
+
+// Fetch the news periodically and call `notify` with each new article that mentions a
+// followed team, when news notifications are on. Nothing is fetched while the poller
+// has found the network down.
+pub async fn run_news<F, N>(
+    store: Arc<MatchStore>,
+    source: SharedSource,
+    feed: Arc<NewsFeed>,
+    settings: F,
+    mut notify: N,
+    period: Duration,
+) where
+    F: Fn() -> Settings,
+    N: FnMut(&NewsArticle, &[String]),
+{
+    let mut interval = interval(period);
+    loop {
+        interval.tick().await;
+        if store.is_offline() {
+            continue;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let added = match feed.refresh(source.as_ref(), now).await {
+            Ok(added) => added,
+            Err(e) => {
+                eprintln!("Error fetching news: {}", e);
+                continue;
+            }
+        };
+        let settings = settings();
+        if !settings.news.notify {
+            continue;
+        }
+        for article in &added {
+            let teams = mentioned_teams(article, &settings.follow.teams);
+            if !teams.is_empty() {
+                notify(article, &teams);
+            }
+        }
+    }
+}
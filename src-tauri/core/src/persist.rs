@@ -0,0 +1,32 @@
+// State the app keeps in JSON files in its data directory: the webhook queue, calendar
+// revisions, reminders, ratings, rankings, the team registry, news and the logo index.
+// A missing or unreadable file is an empty state, and a failed save is logged rather
+// than failing whatever changed the state.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// What is stored at `path`, or the default when there is nothing readable
+pub fn load_json<T: DeserializeOwned + Default>(path: Option<&Path>) -> T {
+    path.and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+// Write `value` to `path`, creating its directory; without a path nothing is kept.
+// `what` names the state in the error that is logged.
+pub fn save_json<T: Serialize + ?Sized>(path: Option<&Path>, value: &T, what: &str) {
+    let Some(path) = path else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let result = serde_json::to_string(value)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| fs::write(path, contents).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("Error saving {}: {}", what, e);
+    }
+}
@@ -0,0 +1,210 @@
+// Per-player stats for a map, scraped from the vlr.gg match page.
+//
+// The page has one `vm-stats-game` section per map (plus an "all maps" summary) with
+// a header giving each team's name and rounds, and one overview table per team.
+// Columns are found by their header text, so a reordered or extended table still
+// parses; stats vlr.gg hasn't filled in yet come back as `None`.
+use crate::api::match_page_path;
+use crate::models::{MapScoreboard, MatchSegment, PlayerStats, TeamScoreboard};
+use crate::source::{MatchSource, SourceResult};
+use scraper::{ElementRef, Html, Selector};
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("valid selector")
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Both-sides value of a stat cell; cells without side splits only have the total
+fn cell_value(cell: ElementRef) -> String {
+    match cell.select(&selector(".mod-both")).next() {
+        Some(both) => text(both),
+        None => text(cell),
+    }
+}
+
+// "72%", "/ 15 /", "+4" or "1.23"; blank cells are `None`
+fn number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    cleaned.parse().ok()
+}
+
+fn count(value: &str) -> Option<u32> {
+    number(value).filter(|n| *n >= 0.0).map(|n| n.round() as u32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Rating,
+    Acs,
+    Kills,
+    Deaths,
+    Assists,
+    Kast,
+    Adr,
+    Headshots,
+    FirstKills,
+    FirstDeaths,
+}
+
+fn column(header: &str) -> Option<Column> {
+    match header.trim().to_uppercase().as_str() {
+        "R" | "R2.0" | "RATING" => Some(Column::Rating),
+        "ACS" => Some(Column::Acs),
+        "K" => Some(Column::Kills),
+        "D" => Some(Column::Deaths),
+        "A" => Some(Column::Assists),
+        "KAST" => Some(Column::Kast),
+        "ADR" => Some(Column::Adr),
+        "HS%" => Some(Column::Headshots),
+        "FK" => Some(Column::FirstKills),
+        "FD" => Some(Column::FirstDeaths),
+        _ => None,
+    }
+}
+
+fn parse_player(row: ElementRef, columns: &[Option<Column>]) -> Option<PlayerStats> {
+    let player = row.select(&selector("td.mod-player")).next()?;
+    let name = player
+        .select(&selector(".text-of"))
+        .next()
+        .map(text)
+        .unwrap_or_else(|| text(player));
+    if name.is_empty() {
+        return None;
+    }
+    let team = player.select(&selector(".ge-text-light")).next().map(text).unwrap_or_default();
+    let agents = row
+        .select(&selector("td.mod-agents img"))
+        .filter_map(|img| {
+            let value = img.value();
+            value.attr("title").or_else(|| value.attr("alt")).map(str::to_string)
+        })
+        .collect();
+
+    let mut stats = PlayerStats {
+        name,
+        team,
+        agents,
+        rating: None,
+        acs: None,
+        kills: None,
+        deaths: None,
+        assists: None,
+        kast: None,
+        adr: None,
+        headshot_percent: None,
+        first_kills: None,
+        first_deaths: None,
+    };
+    for (cell, column) in row.select(&selector("td")).zip(columns) {
+        let Some(column) = column else {
+            continue;
+        };
+        let value = cell_value(cell);
+        match column {
+            Column::Rating => stats.rating = number(&value),
+            Column::Acs => stats.acs = count(&value),
+            Column::Kills => stats.kills = count(&value),
+            Column::Deaths => stats.deaths = count(&value),
+            Column::Assists => stats.assists = count(&value),
+            Column::Kast => stats.kast = count(&value),
+            Column::Adr => stats.adr = count(&value),
+            Column::Headshots => stats.headshot_percent = count(&value),
+            Column::FirstKills => stats.first_kills = count(&value),
+            Column::FirstDeaths => stats.first_deaths = count(&value),
+        }
+    }
+    Some(stats)
+}
+
+fn parse_table(table: ElementRef) -> Vec<PlayerStats> {
+    let columns: Vec<Option<Column>> = table
+        .select(&selector("thead th"))
+        .map(|th| column(&text(th)))
+        .collect();
+    table
+        .select(&selector("tbody tr"))
+        .filter_map(|row| parse_player(row, &columns))
+        .collect()
+}
+
+struct GameSection<'a> {
+    map: String,
+    element: ElementRef<'a>,
+}
+
+// Map sections in series order, without the "all maps" summary
+fn game_sections(document: &Html) -> Vec<GameSection<'_>> {
+    document
+        .select(&selector(".vm-stats-game"))
+        .filter(|game| game.value().attr("data-game-id") != Some("all"))
+        .map(|game| {
+            // The map name is followed by a "PICK" badge in the same element
+            let map = game
+                .select(&selector(".vm-stats-game-header .map span"))
+                .next()
+                .and_then(|span| span.text().map(str::trim).find(|t| !t.is_empty()))
+                .unwrap_or_default()
+                .to_string();
+            GameSection { map, element: game }
+        })
+        .collect()
+}
+
+fn team_header(game: ElementRef, index: usize, players: Vec<PlayerStats>) -> TeamScoreboard {
+    let header = game.select(&selector(".vm-stats-game-header .team")).nth(index);
+    TeamScoreboard {
+        team: header
+            .and_then(|team| team.select(&selector(".team-name")).next())
+            .map(text)
+            .unwrap_or_default(),
+        score: header
+            .and_then(|team| team.select(&selector(".score")).next())
+            .and_then(|score| count(&text(score))),
+        players,
+    }
+}
+
+// Scoreboard for a map of the match page, picked by name (case-insensitive) or else by
+// its 1-based number. `None` when the page has no such map or no stats for it yet.
+pub fn parse_scoreboard(html: &str, match_id: &str, map: &str, map_number: u32) -> Option<MapScoreboard> {
+    let document = Html::parse_document(html);
+    let games = game_sections(&document);
+    let wanted = map.trim();
+    let index = games
+        .iter()
+        .position(|g| !wanted.is_empty() && g.map.eq_ignore_ascii_case(wanted))
+        .or_else(|| map_number.checked_sub(1).map(|i| i as usize).filter(|i| *i < games.len()))?;
+    let game = &games[index];
+
+    let overview = selector("table.wf-table-inset.mod-overview");
+    let mut tables = game.element.select(&overview);
+    let players1 = tables.next().map(parse_table).unwrap_or_default();
+    let players2 = tables.next().map(parse_table).unwrap_or_default();
+    if players1.is_empty() && players2.is_empty() {
+        return None;
+    }
+    Some(MapScoreboard {
+        match_id: match_id.to_string(),
+        map: game.map.clone(),
+        map_number: index as u32 + 1,
+        team1: team_header(game.element, 0, players1),
+        team2: team_header(game.element, 1, players2),
+    })
+}
+
+// Scoreboard for the map a live match is on
+pub async fn current_map_scoreboard(source: &dyn MatchSource, segment: &MatchSegment) -> SourceResult<MapScoreboard> {
+    let path = match_page_path(&segment.match_page)
+        .ok_or_else(|| format!("{} vs {} has no match page", segment.team1, segment.team2))?;
+    let html = source.match_page(&path).await?;
+    let map_number = segment.map_number.trim().parse().unwrap_or(0);
+    parse_scoreboard(&html, &segment.match_id, &segment.current_map, map_number)
+        .ok_or_else(|| format!("No player stats yet for {} vs {}", segment.team1, segment.team2).into())
+}
@@ -0,0 +1,90 @@
+use crate::snapshot::{MatchEvent, MatchSnapshot, MatchStore};
+use crate::source::{is_offline_error, MatchSource, SourceResult};
+use crate::MatchSegment;
+use std::collections::HashMap;
+use tokio::time::{interval, Duration};
+
+pub const POLL_INTERVAL: Duration = Duration::from_secs(30);
+// While offline, how often the reachability probe runs instead of a full poll
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Fetch live matches, their odds and the upcoming schedule once, updating the store.
+// `prepare` fills in what depends on the caller's settings (start times in the display
+// timezone, for one) before the matches are stored.
+pub async fn poll_once<P>(store: &MatchStore, source: &dyn MatchSource, prepare: P) -> SourceResult<Vec<MatchEvent>>
+where
+    P: Fn(Vec<MatchSegment>) -> Vec<MatchSegment>,
+{
+    let matches = prepare(source.live_matches().await?);
+
+    let mut odds = HashMap::new();
+    for segment in &matches {
+        match source.odds(&segment.team1, &segment.team2, segment.starts_at).await {
+            Ok(match_odds) => {
+                odds.insert(segment.match_id.clone(), match_odds);
+            }
+            Err(e) => {
+                eprintln!("Error fetching odds for {}: {}", segment.match_id, e);
+            }
+        }
+    }
+
+    let events = store.update(matches, odds);
+
+    // A failed schedule fetch keeps the previous list rather than failing the poll
+    match source.upcoming_matches().await {
+        Ok(upcoming) => store.set_upcoming(prepare(upcoming)),
+        Err(e) => eprintln!("Error fetching upcoming matches: {}", e),
+    }
+
+    Ok(events)
+}
+
+// Probe the source every `period` until its host can be reached again
+pub async fn wait_until_online(source: &dyn MatchSource, period: Duration) {
+    let mut interval = interval(period);
+    loop {
+        interval.tick().await;
+        if source.reachable().await {
+            return;
+        }
+    }
+}
+
+// Poll forever, calling `on_update` with each new snapshot.
+// Subscribers interested in individual changes use `MatchStore::subscribe` instead.
+// When the network is down, polling pauses: the snapshot is marked offline, a cheap
+// probe runs until the API host answers, and the next poll follows right away.
+pub async fn run_poller<P, F>(
+    store: &MatchStore,
+    source: &dyn MatchSource,
+    period: Duration,
+    prepare: P,
+    mut on_update: F,
+) where
+    P: Fn(Vec<MatchSegment>) -> Vec<MatchSegment>,
+    F: FnMut(&MatchSnapshot),
+{
+    let mut interval = interval(period);
+    loop {
+        interval.tick().await;
+        match poll_once(store, source, &prepare).await {
+            Ok(_) => {
+                store.set_offline(None);
+                on_update(&store.snapshot());
+            }
+            Err(e) if is_offline_error(&e) => {
+                eprintln!("Offline, pausing polling: {}", e);
+                if store.snapshot().offline_since.is_none() {
+                    store.set_offline(Some(chrono::Utc::now().timestamp()));
+                    on_update(&store.snapshot());
+                }
+                wait_until_online(source, PROBE_INTERVAL).await;
+                interval.reset_immediately();
+            }
+            Err(e) => {
+                eprintln!("Error fetching matches: {}", e);
+            }
+        }
+    }
+}
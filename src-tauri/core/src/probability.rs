@@ -0,0 +1,241 @@
+// Live win-probability estimate from the round state, compared against the market.
+//
+// Rounds are treated as independent. Team 1's chance of winning a round is its
+// estimated strength, nudged towards whichever side it is defending; the strength
+// starts from a prior (50% unless given) and moves towards the share of rounds won
+// on the current map as more are played. Map and series chances follow exactly from
+// those round chances, so the same inputs always give the same numbers.
+use crate::snapshot::map_rounds;
+use crate::{MatchSegment, PolymarketOdds};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Round win chance added on defense and removed on attack
+pub const DEFENSE_ADVANTAGE: f64 = 0.02;
+// How many rounds of evidence the prior is worth
+const PRIOR_ROUNDS: f64 = 24.0;
+const ROUNDS_TO_WIN: u32 = 13;
+const HALF_LENGTH: u32 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Attack,
+    Defense,
+}
+
+impl Side {
+    fn opposite(self) -> Side {
+        match self {
+            Side::Attack => Side::Defense,
+            Side::Defense => Side::Attack,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct WinProbability {
+    // Team 1's chance to win the current map
+    pub map1: f64,
+    // Team 1's chance to win the series
+    pub series1: f64,
+    pub best_of: u32,
+    // Team 1's chance implied by Polymarket prices, with the overround removed
+    pub market1: Option<f64>,
+    // series1 - market1: positive means the model rates team 1 higher than the market
+    pub edge1: Option<f64>,
+}
+
+// Round win chance for team 1 on a given side
+fn round_chance(strength: f64, side: Side) -> f64 {
+    let chance = match side {
+        Side::Defense => strength + DEFENSE_ADVANTAGE,
+        Side::Attack => strength - DEFENSE_ADVANTAGE,
+    };
+    chance.clamp(0.01, 0.99)
+}
+
+// Side team 1 plays in round `round` (0-based), given the side it started on.
+// Sides swap at half time and every round in overtime.
+fn side_in_round(start: Side, round: u32) -> Side {
+    let swapped = if round < HALF_LENGTH {
+        false
+    } else if round < 2 * HALF_LENGTH {
+        true
+    } else {
+        (round - 2 * HALF_LENGTH) % 2 == 1
+    };
+    if swapped {
+        start.opposite()
+    } else {
+        start
+    }
+}
+
+// Starting side of team 1, when the half splits tell it. Team 1 is on defense for
+// the rounds it won on CT and the rounds team 2 won on T. The starting side is the one
+// with every first-half round, or exactly a full half once past half time.
+pub fn starting_side(segment: &MatchSegment) -> Option<Side> {
+    let value = |v: &str| v.trim().parse::<u32>().unwrap_or(0);
+    let defense = value(&segment.team1_round_ct) + value(&segment.team2_round_t);
+    let attack = value(&segment.team1_round_t) + value(&segment.team2_round_ct);
+    match (defense, attack) {
+        (0, 0) => None,
+        (_, 0) if defense <= HALF_LENGTH => Some(Side::Defense),
+        (0, _) if attack <= HALF_LENGTH => Some(Side::Attack),
+        (HALF_LENGTH, a) if a < HALF_LENGTH => Some(Side::Defense),
+        (d, HALF_LENGTH) if d < HALF_LENGTH => Some(Side::Attack),
+        // Overtime or inconsistent splits
+        _ => None,
+    }
+}
+
+// Chance team 1 wins the map from `rounds1`-`rounds2`, with team 1 starting on `start`
+pub fn map_win_chance(rounds1: u32, rounds2: u32, strength: f64, start: Side) -> f64 {
+    let mut memo = HashMap::new();
+    map_from(rounds1, rounds2, strength, start, &mut memo)
+}
+
+fn map_from(
+    a: u32,
+    b: u32,
+    strength: f64,
+    start: Side,
+    memo: &mut HashMap<(u32, u32), f64>,
+) -> f64 {
+    if a >= ROUNDS_TO_WIN && a >= b + 2 {
+        return 1.0;
+    }
+    if b >= ROUNDS_TO_WIN && b >= a + 2 {
+        return 0.0;
+    }
+    if let Some(chance) = memo.get(&(a, b)) {
+        return *chance;
+    }
+    let round = a + b;
+    let chance = if a >= HALF_LENGTH && a == b {
+        // Overtime from a tie: two rounds, one on each side, until someone wins both
+        let first = round_chance(strength, side_in_round(start, round));
+        let second = round_chance(strength, side_in_round(start, round + 1));
+        let both = first * second;
+        both / (both + (1.0 - first) * (1.0 - second))
+    } else {
+        let p = round_chance(strength, side_in_round(start, round));
+        p * map_from(a + 1, b, strength, start, memo) + (1.0 - p) * map_from(a, b + 1, strength, start, memo)
+    };
+    memo.insert((a, b), chance);
+    chance
+}
+
+// Map chance when the starting side is unknown: either side is equally likely
+fn map_chance_any_side(rounds1: u32, rounds2: u32, strength: f64, side: Option<Side>) -> f64 {
+    match side {
+        Some(side) => map_win_chance(rounds1, rounds2, strength, side),
+        None => {
+            (map_win_chance(rounds1, rounds2, strength, Side::Defense)
+                + map_win_chance(rounds1, rounds2, strength, Side::Attack))
+                / 2.0
+        }
+    }
+}
+
+// Round strength giving a fresh map win chance of `map_chance`
+pub fn strength_for_map_chance(map_chance: f64) -> f64 {
+    let target = map_chance.clamp(0.01, 0.99);
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..50 {
+        let mid = (low + high) / 2.0;
+        if map_chance_any_side(0, 0, mid, None) < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+// Chance team 1 takes `need1` more maps before team 2 takes `need2`
+pub fn series_win_chance(need1: u32, need2: u32, map_chance: f64) -> f64 {
+    if need1 == 0 {
+        return 1.0;
+    }
+    if need2 == 0 {
+        return 0.0;
+    }
+    map_chance * series_win_chance(need1 - 1, need2, map_chance)
+        + (1.0 - map_chance) * series_win_chance(need1, need2 - 1, map_chance)
+}
+
+// Series length: best of 5 for grand and lower finals or once a team has two maps,
+// otherwise best of 3
+pub fn best_of(segment: &MatchSegment) -> u32 {
+    let series = segment.match_series.to_lowercase();
+    let score = |v: &str| v.trim().parse::<u32>().unwrap_or(0);
+    if series.contains("bo5")
+        || series.contains("grand final")
+        || series.contains("lower final")
+        || score(&segment.score1).max(score(&segment.score2)) >= 2
+    {
+        5
+    } else if series.contains("bo1") {
+        1
+    } else {
+        3
+    }
+}
+
+// Team 1's chance implied by the market prices
+pub fn implied_chance(odds: &PolymarketOdds) -> Option<f64> {
+    match (odds.team1_odds, odds.team2_odds) {
+        (Some(p1), Some(p2)) if p1 + p2 > 0.0 => Some(p1 / (p1 + p2)),
+        (Some(p1), None) => Some(p1),
+        (None, Some(p2)) => Some(1.0 - p2),
+        _ => None,
+    }
+}
+
+// Estimate with a 50% prior for each map
+pub fn estimate(segment: &MatchSegment, odds: Option<&PolymarketOdds>) -> WinProbability {
+    estimate_with_prior(segment, odds, 0.5)
+}
+
+// `prior_map` is team 1's chance to win a map before it starts, e.g. from ratings
+pub fn estimate_with_prior(
+    segment: &MatchSegment,
+    odds: Option<&PolymarketOdds>,
+    prior_map: f64,
+) -> WinProbability {
+    let prior = strength_for_map_chance(prior_map);
+    let (rounds1, rounds2) = map_rounds(segment);
+    let played = (rounds1 + rounds2) as f64;
+    let strength = (rounds1 as f64 + prior * PRIOR_ROUNDS) / (played + PRIOR_ROUNDS);
+
+    let live = segment.time_until_match == "LIVE";
+    let map1 = if live {
+        map_chance_any_side(rounds1, rounds2, strength, starting_side(segment))
+    } else {
+        map_chance_any_side(0, 0, prior, None)
+    };
+
+    // Later maps start fresh at the updated strength
+    let next_maps = map_chance_any_side(0, 0, strength, None);
+    let best_of = best_of(segment);
+    let to_win = best_of / 2 + 1;
+    let score = |v: &str| v.trim().parse::<u32>().unwrap_or(0);
+    let need1 = to_win.saturating_sub(score(&segment.score1));
+    let need2 = to_win.saturating_sub(score(&segment.score2));
+    let series1 = if need1 == 0 || need2 == 0 {
+        series_win_chance(need1, need2, next_maps)
+    } else {
+        map1 * series_win_chance(need1 - 1, need2, next_maps)
+            + (1.0 - map1) * series_win_chance(need1, need2 - 1, next_maps)
+    };
+
+    let market1 = odds.and_then(implied_chance);
+    WinProbability {
+        map1,
+        series1,
+        best_of,
+        market1,
+        edge1: market1.map(|market| series1 - market),
+    }
+}
@@ -0,0 +1,159 @@
+// vlr.gg regional team rankings, cached on disk for a day.
+//
+// Rankings change at most daily, so each region is fetched once a day and served from
+// the cache in between; a failed refresh keeps serving the stale ranking. Live and
+// upcoming matches are annotated with the rank of each team from whichever cached
+// region lists it.
+use crate::models::{team_to_slug, MatchSegment, RankedTeam, TeamRank};
+use crate::persist::{load_json, save_json};
+use crate::snapshot::MatchStore;
+use crate::source::{MatchSource, SharedSource, SourceResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration};
+
+// In the app data directory, where the CLI reads the app's cached rankings
+pub const RANKINGS_FILE: &str = "rankings.json";
+// How long a fetched ranking is used before it is fetched again
+pub const RANKINGS_MAX_AGE_SECS: i64 = 24 * 3600;
+// How often stale regions are looked for
+pub const RANKINGS_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+// vlrggapi region codes and how the card labels them
+pub const REGIONS: &[(&str, &str)] = &[
+    ("na", "NA"),
+    ("eu", "EMEA"),
+    ("br", "BR"),
+    ("ap", "APAC"),
+    ("kr", "KR"),
+    ("ch", "CN"),
+    ("jp", "JP"),
+    ("lan", "LAN"),
+    ("las", "LAS"),
+    ("oce", "OCE"),
+    ("mn", "MENA"),
+    ("gc", "GC"),
+    ("col", "COL"),
+];
+
+// Display name for a region code, `None` for codes the API doesn't know
+pub fn region_name(region: &str) -> Option<&'static str> {
+    REGIONS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(region.trim()))
+        .map(|(_, name)| *name)
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RegionRankings {
+    pub fetched_at: i64,
+    pub teams: Vec<RankedTeam>,
+}
+
+// Rankings per region code and where they are persisted
+pub struct RankingCache {
+    path: Option<PathBuf>,
+    regions: Mutex<HashMap<String, RegionRankings>>,
+}
+
+impl RankingCache {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let regions = load_json(path.as_deref());
+        RankingCache {
+            path,
+            regions: Mutex::new(regions),
+        }
+    }
+
+    fn save(&self, regions: &HashMap<String, RegionRankings>) {
+        save_json(self.path.as_deref(), regions, "rankings");
+    }
+
+    // Cached ranking for a region, however old
+    pub fn cached(&self, region: &str) -> Option<RegionRankings> {
+        self.regions.lock().unwrap().get(&region.to_lowercase()).cloned()
+    }
+
+    fn is_fresh(&self, region: &str, now: i64) -> bool {
+        self.cached(region)
+            .is_some_and(|cached| now - cached.fetched_at < RANKINGS_MAX_AGE_SECS)
+    }
+
+    pub fn insert(&self, region: &str, teams: Vec<RankedTeam>, now: i64) {
+        let mut regions = self.regions.lock().unwrap();
+        regions.insert(region.to_lowercase(), RegionRankings { fetched_at: now, teams });
+        self.save(&regions);
+    }
+
+    // Ranking for a region, fetched when the cached one is missing or a day old.
+    // A failed fetch falls back to the stale ranking if there is one.
+    pub async fn get(&self, source: &dyn MatchSource, region: &str, now: i64) -> SourceResult<Vec<RankedTeam>> {
+        let region = region.trim().to_lowercase();
+        if region_name(&region).is_none() {
+            return Err(format!("Unknown region '{}'", region).into());
+        }
+        if self.is_fresh(&region, now) {
+            if let Some(cached) = self.cached(&region) {
+                return Ok(cached.teams);
+            }
+        }
+        match source.rankings(&region).await {
+            Ok(teams) => {
+                self.insert(&region, teams.clone(), now);
+                Ok(teams)
+            }
+            Err(e) => match self.cached(&region) {
+                Some(cached) => {
+                    eprintln!("Error fetching {} rankings, using the cached ones: {}", region, e);
+                    Ok(cached.teams)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    // Rank of a team in the first region (in `REGIONS` order) that lists it
+    pub fn rank(&self, team: &str) -> Option<TeamRank> {
+        let slug = team_to_slug(team);
+        let regions = self.regions.lock().unwrap();
+        REGIONS.iter().find_map(|(code, name)| {
+            let ranked = regions.get(*code)?.teams.iter().find(|t| team_to_slug(&t.team) == slug)?;
+            Some(TeamRank {
+                rank: ranked.rank,
+                region: code.to_string(),
+                region_name: name.to_string(),
+            })
+        })
+    }
+
+    // Fill in `rank1`/`rank2` from the cached rankings
+    pub fn annotate(&self, mut matches: Vec<MatchSegment>) -> Vec<MatchSegment> {
+        for segment in &mut matches {
+            segment.rank1 = self.rank(&segment.team1);
+            segment.rank2 = self.rank(&segment.team2);
+        }
+        matches
+    }
+}
+
+// Keep every region's ranking no more than a day old. Checks are skipped while the
+// poller has found the network down.
+pub async fn run_rankings(store: Arc<MatchStore>, source: SharedSource, cache: Arc<RankingCache>, period: Duration) {
+    let mut interval = interval(period);
+    loop {
+        interval.tick().await;
+        if store.is_offline() {
+            continue;
+        }
+        let now = chrono::Utc::now().timestamp();
+        for (region, _) in REGIONS {
+            if !cache.is_fresh(region, now) {
+                if let Err(e) = cache.get(source.as_ref(), region, now).await {
+                    eprintln!("Error fetching {} rankings: {}", region, e);
+                }
+            }
+        }
+    }
+}
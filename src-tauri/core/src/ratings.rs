@@ -0,0 +1,198 @@
+// Team Elo ratings built from completed matches in the results feed.
+//
+// Every map counts as one game, so a 2-1 series is two wins and a loss. The expected
+// score between two ratings is therefore a map win chance; series chances follow from
+// it the same way the live model works them out. Results are applied once each (by
+// match id) and the ratings are persisted so they build up across restarts.
+use crate::models::{team_to_slug, MatchResult};
+use crate::persist::{load_json, save_json};
+use crate::probability::series_win_chance;
+use crate::snapshot::MatchStore;
+use crate::source::SharedSource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration};
+
+pub const INITIAL_RATING: f64 = 1500.0;
+// Rating points at stake per map
+const K_FACTOR: f64 = 24.0;
+// The results feed only covers the last few days, so it is checked now and then
+pub const RESULTS_INTERVAL: Duration = Duration::from_secs(15 * 60);
+// Applied match ids kept to skip results that are still in the feed
+const SEEN_LIMIT: usize = 2000;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamRating {
+    // Team name as last seen in the results
+    pub team: String,
+    pub rating: f64,
+    pub maps_won: u32,
+    pub maps_lost: u32,
+    pub matches: u32,
+    pub updated_at: i64,
+}
+
+// Pre-match expectation for a pairing, team 1's point of view
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RatingPrediction {
+    pub team1: String,
+    pub team2: String,
+    pub rating1: f64,
+    pub rating2: f64,
+    pub map1: f64,
+    pub series1: f64,
+    pub best_of: u32,
+    // Both teams have recorded results; otherwise the unknown side is rated at the start value
+    pub rated: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(default)]
+struct RatingState {
+    // Keyed by team slug so name variants share a rating
+    teams: HashMap<String, TeamRating>,
+    // Applied match ids, oldest first
+    seen: Vec<String>,
+}
+
+// Chance a team rated `rating1` wins a map against one rated `rating2`
+pub fn expected_score(rating1: f64, rating2: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((rating2 - rating1) / 400.0))
+}
+
+fn parse_maps(value: &str) -> Option<u32> {
+    value.trim().parse().ok()
+}
+
+// Ratings and the results already applied, and where they are persisted
+pub struct RatingBook {
+    path: Option<PathBuf>,
+    state: Mutex<RatingState>,
+}
+
+impl RatingBook {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let state = load_json(path.as_deref());
+        RatingBook {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn save(&self, state: &RatingState) {
+        save_json(self.path.as_deref(), state, "team ratings");
+    }
+
+    // Apply results not seen before, oldest first as the feed lists the newest first.
+    // Returns how many were applied.
+    pub fn record(&self, results: &[MatchResult], now: i64) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let mut applied = 0;
+
+        for result in results.iter().rev() {
+            if state.seen.contains(&result.match_id) {
+                continue;
+            }
+            // Unplayed or unfinished series (e.g. forfeits listed as 0-0) carry no information
+            let (Some(won1), Some(won2)) = (parse_maps(&result.score1), parse_maps(&result.score2)) else {
+                continue;
+            };
+            if won1 == won2 {
+                continue;
+            }
+
+            let (key1, key2) = (team_to_slug(&result.team1), team_to_slug(&result.team2));
+            let rating = |state: &RatingState, key: &str| {
+                state.teams.get(key).map(|t| t.rating).unwrap_or(INITIAL_RATING)
+            };
+            let (rating1, rating2) = (rating(&state, &key1), rating(&state, &key2));
+            let expected = expected_score(rating1, rating2);
+            let delta = K_FACTOR * (won1 as f64 - (won1 + won2) as f64 * expected);
+
+            for (key, team, change, won, lost) in [
+                (key1, &result.team1, delta, won1, won2),
+                (key2, &result.team2, -delta, won2, won1),
+            ] {
+                let entry = state.teams.entry(key).or_insert_with(|| TeamRating {
+                    team: team.clone(),
+                    rating: INITIAL_RATING,
+                    maps_won: 0,
+                    maps_lost: 0,
+                    matches: 0,
+                    updated_at: now,
+                });
+                entry.team = team.clone();
+                entry.rating += change;
+                entry.maps_won += won;
+                entry.maps_lost += lost;
+                entry.matches += 1;
+                entry.updated_at = now;
+            }
+
+            state.seen.push(result.match_id.clone());
+            applied += 1;
+        }
+
+        if applied > 0 {
+            let excess = state.seen.len().saturating_sub(SEEN_LIMIT);
+            state.seen.drain(..excess);
+            self.save(&state);
+        }
+        applied
+    }
+
+    // All rated teams, highest first
+    pub fn ratings(&self) -> Vec<TeamRating> {
+        let mut ratings: Vec<TeamRating> = self.state.lock().unwrap().teams.values().cloned().collect();
+        ratings.sort_by(|a, b| b.rating.total_cmp(&a.rating).then_with(|| a.team.cmp(&b.team)));
+        ratings
+    }
+
+    pub fn rating(&self, team: &str) -> Option<TeamRating> {
+        self.state.lock().unwrap().teams.get(&team_to_slug(team)).cloned()
+    }
+
+    // Team 1's map win chance; unrated teams count as the start value
+    pub fn map_chance(&self, team1: &str, team2: &str) -> f64 {
+        self.predict(team1, team2, 1).map1
+    }
+
+    pub fn predict(&self, team1: &str, team2: &str, best_of: u32) -> RatingPrediction {
+        let (known1, known2) = (self.rating(team1), self.rating(team2));
+        let rating1 = known1.as_ref().map(|t| t.rating).unwrap_or(INITIAL_RATING);
+        let rating2 = known2.as_ref().map(|t| t.rating).unwrap_or(INITIAL_RATING);
+        let map1 = expected_score(rating1, rating2);
+        let best_of = best_of.max(1);
+        let to_win = best_of / 2 + 1;
+        RatingPrediction {
+            team1: team1.to_string(),
+            team2: team2.to_string(),
+            rating1,
+            rating2,
+            map1,
+            series1: series_win_chance(to_win, to_win, map1),
+            best_of,
+            rated: known1.is_some() && known2.is_some(),
+        }
+    }
+}
+
+// Fetch the results feed periodically and fold new results into the ratings,
+// except while the poller has found the network down
+pub async fn run_ratings(store: Arc<MatchStore>, source: SharedSource, book: Arc<RatingBook>, period: Duration) {
+    let mut interval = interval(period);
+    loop {
+        interval.tick().await;
+        if store.is_offline() {
+            continue;
+        }
+        match source.results().await {
+            Ok(results) => {
+                book.record(&results, chrono::Utc::now().timestamp());
+            }
+            Err(e) => eprintln!("Error fetching match results: {}", e),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+// Notifications shortly before followed matches start. Scheduled reminders are
+// persisted so they still fire after a restart, and move with the match when the
+// API reports a new start time.
+use crate::persist::{load_json, save_json};
+use crate::settings::{FollowSettings, ReminderSettings, Settings};
+use crate::snapshot::{MatchSnapshot, MatchStore};
+use crate::MatchSegment;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+
+const REMINDER_TICK: Duration = Duration::from_secs(15);
+// Reminders for matches that started this long ago are dropped
+const RETENTION_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Reminder {
+    pub match_id: String,
+    pub team1: String,
+    pub team2: String,
+    pub event: String,
+    pub starts_at: i64,
+    pub fire_at: i64,
+    pub fired: bool,
+    // The start moved since the reminder was first scheduled
+    pub rescheduled: bool,
+}
+
+impl Reminder {
+    pub fn title(&self) -> String {
+        format!("{} vs {}", self.team1, self.team2)
+    }
+
+    // e.g. "Delayed, starts in 15 minutes · VCT 2025: Americas Stage 1"
+    pub fn body(&self, now: i64) -> String {
+        let minutes = ((self.starts_at - now).max(0) + 59) / 60;
+        let starts = match minutes {
+            0 => "Starting now".to_string(),
+            1 => "Starts in 1 minute".to_string(),
+            n => format!("Starts in {} minutes", n),
+        };
+        let starts = if self.rescheduled {
+            format!("Delayed, {}", starts.to_lowercase())
+        } else {
+            starts
+        };
+        if self.event.is_empty() {
+            starts
+        } else {
+            format!("{} · {}", starts, self.event)
+        }
+    }
+}
+
+// Scheduled reminders and where they are persisted
+pub struct ReminderScheduler {
+    path: Option<PathBuf>,
+    pub reminders: Vec<Reminder>,
+}
+
+impl ReminderScheduler {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let reminders = load_json(path.as_deref());
+        ReminderScheduler { path, reminders }
+    }
+
+    fn save(&self) {
+        save_json(self.path.as_deref(), &self.reminders, "reminders");
+    }
+
+    // Schedule reminders for followed matches that haven't started and move the ones
+    // whose start time or lead time changed. Pending reminders are dropped when their
+    // match is unfollowed, and all of them when reminders are turned off.
+    pub fn sync(&mut self, matches: &[MatchSegment], follow: &FollowSettings, settings: &ReminderSettings, now: i64) {
+        let lead = settings.minutes_before as i64 * 60;
+        let before = self.reminders.len();
+        self.reminders.retain(|r| {
+            r.fired || (settings.enabled && follow.follows_match(&r.team1, &r.team2, &r.event))
+        });
+        let mut changed = self.reminders.len() != before;
+        if !settings.enabled {
+            if changed {
+                self.save();
+            }
+            return;
+        }
+
+        // Pending reminders follow the current lead time when it is changed
+        for reminder in self.reminders.iter_mut().filter(|r| !r.fired) {
+            if reminder.fire_at != reminder.starts_at - lead {
+                reminder.fire_at = reminder.starts_at - lead;
+                changed = true;
+            }
+        }
+
+        for segment in matches.iter().filter(|m| follow.follows(m)) {
+            let Some(starts_at) = segment.starts_at else {
+                continue;
+            };
+            let existing = self.reminders.iter_mut().find(|r| r.match_id == segment.match_id);
+            match existing {
+                Some(reminder) if reminder.starts_at == starts_at => {}
+                Some(reminder) => {
+                    // A delayed start gets a fresh reminder; an earlier one only moves
+                    // a reminder that hasn't fired yet
+                    if starts_at > reminder.starts_at {
+                        reminder.fired = false;
+                        reminder.rescheduled = true;
+                    }
+                    reminder.starts_at = starts_at;
+                    reminder.fire_at = starts_at - lead;
+                    changed = true;
+                }
+                None if starts_at > now => {
+                    self.reminders.push(Reminder {
+                        match_id: segment.match_id.clone(),
+                        team1: segment.team1.clone(),
+                        team2: segment.team2.clone(),
+                        event: segment.match_event.clone(),
+                        starts_at,
+                        fire_at: starts_at - lead,
+                        fired: false,
+                        rescheduled: false,
+                    });
+                    changed = true;
+                }
+                None => {}
+            }
+        }
+
+        let before = self.reminders.len();
+        self.reminders.retain(|r| r.starts_at > now - RETENTION_SECS);
+        if changed || self.reminders.len() != before {
+            self.save();
+        }
+    }
+
+    // Reminders to show now. Ones that came due while the app was closed still fire
+    // if the match hasn't started; the rest are skipped.
+    pub fn take_due(&mut self, now: i64) -> Vec<Reminder> {
+        let mut due = Vec::new();
+        let mut changed = false;
+        for reminder in self.reminders.iter_mut().filter(|r| !r.fired && r.fire_at <= now) {
+            reminder.fired = true;
+            changed = true;
+            if reminder.starts_at > now {
+                due.push(reminder.clone());
+            }
+        }
+        if changed {
+            self.save();
+        }
+        due
+    }
+}
+
+fn upcoming_and_live(snapshot: &MatchSnapshot) -> Vec<MatchSegment> {
+    snapshot.upcoming.iter().chain(&snapshot.matches).cloned().collect()
+}
+
+// Check the schedule periodically and call `notify` for each reminder that is due.
+// While offline the schedule is stale, so checks wait until the poller is back.
+pub async fn run_reminders<F, N>(store: Arc<MatchStore>, mut scheduler: ReminderScheduler, settings: F, mut notify: N)
+where
+    F: Fn() -> Settings,
+    N: FnMut(&Reminder, i64),
+{
+    let mut interval = interval(REMINDER_TICK);
+    loop {
+        interval.tick().await;
+        if store.is_offline() {
+            continue;
+        }
+        let settings = settings();
+        let now = chrono::Utc::now().timestamp();
+        let matches = upcoming_and_live(&store.snapshot());
+        scheduler.sync(&matches, &settings.follow, &settings.reminders, now);
+        for reminder in scheduler.take_due(now) {
+            notify(&reminder, now);
+        }
+    }
+}
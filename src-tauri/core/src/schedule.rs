@@ -0,0 +1,137 @@
+// Match start times: parsing the API's timestamps, showing them in the configured
+// timezone and counting down to them.
+use crate::models::MatchSegment;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+// Polymarket dates its Valorant game slugs in US Eastern time
+const MARKET_TIMEZONE: Tz = chrono_tz::America::New_York;
+
+// Timezone start times are shown in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayZone {
+    // The system timezone
+    Local,
+    Named(Tz),
+}
+
+impl DisplayZone {
+    // IANA name such as "Europe/Berlin"; empty or "local" means the system timezone
+    pub fn parse(name: Option<&str>) -> Result<Self, String> {
+        match name.map(str::trim) {
+            None | Some("") => Ok(DisplayZone::Local),
+            Some(name) if name.eq_ignore_ascii_case("local") => Ok(DisplayZone::Local),
+            Some(name) => name
+                .parse::<Tz>()
+                .map(DisplayZone::Named)
+                .map_err(|_| format!("Unknown timezone '{}'", name)),
+        }
+    }
+
+    // Wall-clock time and zone abbreviation for an instant
+    fn localize(&self, time: DateTime<Utc>) -> (NaiveDateTime, String) {
+        match self {
+            DisplayZone::Local => {
+                let local = time.with_timezone(&chrono::Local);
+                (local.naive_local(), local.format("%:z").to_string())
+            }
+            DisplayZone::Named(tz) => {
+                let local = time.with_timezone(tz);
+                (local.naive_local(), local.format("%Z").to_string())
+            }
+        }
+    }
+}
+
+// Start time from the API's `unix_timestamp`, which is either unix seconds or a
+// UTC "YYYY-MM-DD HH:MM:SS" string depending on the endpoint
+pub fn parse_start(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = value.parse::<i64>() {
+        return DateTime::from_timestamp(seconds, 0);
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| naive.and_utc())
+        .ok()
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc)))
+}
+
+// "Today 20:00 CEST", "Tomorrow 01:30 CEST", "Sat 20:00 CEST" or "Sat 12 Apr 20:00 CEST".
+// Days are compared in the display timezone, so a match just after midnight there
+// is "Tomorrow" even when it is still today in UTC.
+pub fn format_start_time(start: DateTime<Utc>, zone: DisplayZone, now: DateTime<Utc>) -> String {
+    let (local_start, abbreviation) = zone.localize(start);
+    let (local_now, _) = zone.localize(now);
+    let days = (local_start.date() - local_now.date()).num_days();
+
+    let day = match days {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        -1 => "Yesterday".to_string(),
+        2..=6 => local_start.format("%a").to_string(),
+        _ => local_start.format("%a %-d %b").to_string(),
+    };
+    format!("{} {} {}", day, local_start.format("%H:%M"), abbreviation)
+}
+
+// Fill in `start_time` for matches with a known start. The display timezone comes from
+// the settings of whoever shows the matches, so parsing leaves it empty.
+pub fn with_start_times(mut segments: Vec<MatchSegment>, zone: DisplayZone, now: DateTime<Utc>) -> Vec<MatchSegment> {
+    for segment in &mut segments {
+        segment.start_time = segment
+            .starts_at
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+            .map(|start| format_start_time(start, zone, now))
+            .unwrap_or_default();
+    }
+    segments
+}
+
+// Time left until the start, e.g. "2d 4h", "3h 05m", "12m"; "now" once it has passed
+pub fn countdown(start: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (start - now).num_seconds();
+    if seconds <= 0 {
+        return "now".to_string();
+    }
+    let (days, hours, minutes) = (seconds / 86_400, seconds % 86_400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        "<1m".to_string()
+    }
+}
+
+// Date used in Polymarket market slugs for a match starting at `start`
+pub fn market_date(start: Option<DateTime<Utc>>, now: DateTime<Utc>) -> NaiveDate {
+    start.unwrap_or(now).with_timezone(&MARKET_TIMEZONE).date_naive()
+}
+
+// Upcoming match with its countdown at the time it was requested
+#[derive(Debug, Serialize, Clone)]
+pub struct UpcomingMatch {
+    #[serde(flatten)]
+    pub segment: MatchSegment,
+    pub countdown: String,
+}
+
+pub fn with_countdowns(matches: &[MatchSegment], now: DateTime<Utc>) -> Vec<UpcomingMatch> {
+    matches
+        .iter()
+        .map(|segment| UpcomingMatch {
+            segment: segment.clone(),
+            countdown: segment
+                .starts_at
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .map(|start| countdown(start, now))
+                .unwrap_or_default(),
+        })
+        .collect()
+}
@@ -0,0 +1,275 @@
+use crate::calendar::CalendarFeed;
+use crate::schedule::with_countdowns;
+use crate::settings::ServerSettings;
+use crate::snapshot::MatchStore;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use futures::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+#[derive(Clone)]
+struct ServerContext {
+    store: Arc<MatchStore>,
+    calendar: Arc<CalendarFeed>,
+    token: Option<String>,
+    port: u16,
+}
+
+// Accept the token as a bearer header, or as a `token` query parameter for
+// clients that cannot set headers (browser WebSockets, OBS browser sources)
+fn request_token(request: &Request) -> Option<String> {
+    let from_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.to_string());
+
+    from_header.or_else(|| {
+        request.uri().query().and_then(|query| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == "token")
+                .and_then(|(_, value)| urlencoding::decode(value).ok())
+                .map(|token| token.into_owned())
+        })
+    })
+}
+
+async fn require_token(State(ctx): State<ServerContext>, request: Request, next: Next) -> Response {
+    if let Some(expected) = &ctx.token {
+        if request_token(&request).as_deref() != Some(expected.as_str()) {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+// Whether the request comes from a page served by this server, or from a client that
+// is not a browser page at all (no Origin header)
+fn is_same_origin(request: &Request) -> bool {
+    let Some(origin) = request.headers().get(header::ORIGIN) else {
+        return true;
+    };
+    let host = request.headers().get(header::HOST).and_then(|value| value.to_str().ok());
+    match (origin.to_str().ok(), host) {
+        (Some(origin), Some(host)) => origin.strip_prefix("http://") == Some(host),
+        _ => false,
+    }
+}
+
+// Browsers let any page open a WebSocket or EventSource to localhost, so without a
+// token the streams only accept requests from the server's own pages (the overlay)
+async fn reject_cross_origin(State(ctx): State<ServerContext>, request: Request, next: Next) -> Response {
+    if ctx.token.is_none() && !is_same_origin(&request) {
+        return (StatusCode::FORBIDDEN, "Cross-origin requests need a token").into_response();
+    }
+    next.run(request).await
+}
+
+// Whether the request was addressed to this server by a loopback name. A page on
+// another site that rebinds its own hostname to 127.0.0.1 still sends its own Host.
+fn is_local_host(request: &Request, port: u16) -> bool {
+    let Some(host) = request.headers().get(header::HOST).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+    host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+}
+
+// Without a token, every route only answers requests addressed to localhost, so a
+// DNS-rebinding page can't read the API or the calendar
+async fn reject_foreign_hosts(State(ctx): State<ServerContext>, request: Request, next: Next) -> Response {
+    if ctx.token.is_none() && !is_local_host(&request, ctx.port) {
+        return (StatusCode::FORBIDDEN, "Requests need a localhost Host header or a token").into_response();
+    }
+    next.run(request).await
+}
+
+async fn get_snapshot(State(ctx): State<ServerContext>) -> impl IntoResponse {
+    Json(ctx.store.snapshot())
+}
+
+async fn get_all_odds(State(ctx): State<ServerContext>) -> impl IntoResponse {
+    Json(ctx.store.snapshot().odds)
+}
+
+// Countdowns are computed per request so clients don't need to know the clock
+async fn get_upcoming(State(ctx): State<ServerContext>) -> impl IntoResponse {
+    Json(with_countdowns(&ctx.store.snapshot().upcoming, chrono::Utc::now()))
+}
+
+// Subscribable feed of followed matches
+async fn get_calendar(State(ctx): State<ServerContext>) -> impl IntoResponse {
+    let body = ctx.calendar.render(&ctx.store.snapshot(), chrono::Utc::now());
+    ([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], body)
+}
+
+async fn get_match_odds(
+    State(ctx): State<ServerContext>,
+    Path(match_id): Path<String>,
+) -> Response {
+    match ctx.store.snapshot().odds.get(&match_id) {
+        Some(odds) => Json(odds.clone()).into_response(),
+        None => (StatusCode::NOT_FOUND, "No odds for this match").into_response(),
+    }
+}
+
+async fn events_socket(State(ctx): State<ServerContext>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| stream_events(socket, ctx.store))
+}
+
+// Send the current snapshot, then every diff event until the client disconnects
+async fn stream_events(mut socket: WebSocket, store: Arc<MatchStore>) {
+    let mut events = store.subscribe();
+
+    let initial = serde_json::json!({ "type": "snapshot", "snapshot": store.snapshot() });
+    if socket.send(Message::Text(initial.to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                // A slow client skips missed events rather than disconnecting
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+// OBS browser source page; renders the featured match from `/events`
+async fn overlay_page() -> Html<&'static str> {
+    Html(include_str!("../overlay/overlay.html"))
+}
+
+fn snapshot_event(store: &MatchStore) -> Result<Event, Infallible> {
+    let data = serde_json::to_string(&store.snapshot()).unwrap_or_default();
+    Ok(Event::default().event("snapshot").data(data))
+}
+
+// Server-sent events carrying the full snapshot: once on connect, then after every change
+async fn snapshot_events(
+    State(ctx): State<ServerContext>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = ctx.store.subscribe();
+    let initial = snapshot_event(&ctx.store);
+
+    let updates = stream::unfold((ctx.store, receiver), |(store, mut receiver)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(_) => {
+                    // One poll produces several events; send a single snapshot for the batch
+                    while receiver.try_recv().is_ok() {}
+                    let event = snapshot_event(&store);
+                    return Some((event, (store, receiver)));
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream::once(async move { initial }).chain(updates)).keep_alive(KeepAlive::default())
+}
+
+// `port` is the one the server listens on, for checking the Host header
+pub fn router(store: Arc<MatchStore>, calendar: Arc<CalendarFeed>, token: Option<String>, port: u16) -> Router {
+    let ctx = ServerContext {
+        store,
+        calendar,
+        token: token.filter(|t| !t.is_empty()),
+        port,
+    };
+
+    let streams = Router::new()
+        .route("/ws", get(events_socket))
+        .route("/events", get(snapshot_events))
+        .route_layer(middleware::from_fn_with_state(ctx.clone(), reject_cross_origin));
+
+    Router::new()
+        .route("/api/snapshot", get(get_snapshot))
+        .route("/api/odds", get(get_all_odds))
+        .route("/api/odds/:match_id", get(get_match_odds))
+        .route("/api/upcoming", get(get_upcoming))
+        .route("/overlay", get(overlay_page))
+        .route("/calendar.ics", get(get_calendar))
+        .merge(streams)
+        .layer(middleware::from_fn_with_state(ctx.clone(), require_token))
+        .layer(middleware::from_fn_with_state(ctx.clone(), reject_foreign_hosts))
+        .with_state(ctx)
+}
+
+// Bind to localhost and serve in the background. Binding happens up front so
+// a port that is already in use is reported to the caller.
+pub async fn start_server(
+    store: Arc<MatchStore>,
+    calendar: Arc<CalendarFeed>,
+    settings: &ServerSettings,
+) -> Result<JoinHandle<()>, String> {
+    let listener = TcpListener::bind(("127.0.0.1", settings.port))
+        .await
+        .map_err(|e| format!("Could not bind to port {}: {}", settings.port, e))?;
+    let app = router(store, calendar, settings.token.clone(), settings.port);
+
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("Local server stopped: {}", e);
+        }
+    }))
+}
+
+// Handle to the running server task, if any
+#[derive(Default)]
+pub struct ServerHandle(Mutex<Option<JoinHandle<()>>>);
+
+// Stop the running server and start a new one if enabled in settings
+pub async fn restart_server(
+    store: Arc<MatchStore>,
+    calendar: Arc<CalendarFeed>,
+    handle: &ServerHandle,
+    settings: &ServerSettings,
+) -> Result<(), String> {
+    let previous = handle.0.lock().unwrap().take();
+    if let Some(previous) = previous {
+        previous.abort();
+        // Wait for the listener to be dropped so the port can be reused
+        let _ = previous.await;
+    }
+
+    if !settings.enabled {
+        return Ok(());
+    }
+    let running = start_server(store, calendar, settings).await?;
+    *handle.0.lock().unwrap() = Some(running);
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use crate::filters::{FilterRule, MatchFilter};
+use crate::schedule::DisplayZone;
+use crate::template::{
+    Templates, DEFAULT_MATCH_TEMPLATE, DEFAULT_NOTIFICATION_TEMPLATE, DEFAULT_TOOLTIP_TEMPLATE,
+};
+use crate::webhooks::NotificationKind;
+use crate::MatchSegment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+// Must match `identifier` in tauri.conf.json so the CLI reads the app's settings
+const APP_IDENTIFIER: &str = "com.example.valorant-menubar";
+
+// Global keyboard shortcuts, stored as accelerator strings (e.g. "CommandOrControl+Shift+V").
+// A `None` binding leaves that action without a shortcut.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ShortcutSettings {
+    pub toggle_window: Option<String>,
+    pub next_match: Option<String>,
+    pub previous_match: Option<String>,
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        ShortcutSettings {
+            toggle_window: Some("CommandOrControl+Shift+V".to_string()),
+            next_match: Some("CommandOrControl+Shift+Right".to_string()),
+            previous_match: Some("CommandOrControl+Shift+Left".to_string()),
+        }
+    }
+}
+
+// Window position and size in logical pixels
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct ScoreboardSettings {
+    // Match ids shown in the mini scoreboard; empty shows the first live match
+    pub pinned_matches: Vec<String>,
+    // Monitor the scoreboard was last placed on
+    pub last_monitor: Option<String>,
+    // Last geometry per monitor name, restored when that monitor is connected
+    pub geometry: HashMap<String, WindowGeometry>,
+}
+
+// Optional HTTP/WebSocket server on localhost for overlays, bots and dashboards
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    // When set, requests must send `Authorization: Bearer <token>` or `?token=<token>`
+    pub token: Option<String>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        ServerSettings {
+            enabled: false,
+            port: 7878,
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    Discord,
+    Slack,
+    Json,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    pub format: WebhookFormat,
+    // Events to send; empty sends all of them
+    #[serde(default)]
+    pub events: Vec<NotificationKind>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub endpoints: Vec<WebhookEndpoint>,
+    // Minimum change in implied probability (0-1) reported as an odds swing
+    pub odds_swing_threshold: f64,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            endpoints: Vec::new(),
+            odds_swing_threshold: 0.1,
+        }
+    }
+}
+
+// Match text templates (see template.rs for the syntax)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TemplateSettings {
+    pub tooltip: String,
+    pub tray_item: String,
+    pub notification: String,
+    pub cli: String,
+    // Team flags in front of team names in the tooltip, tray and CLI templates that are
+    // left at their default; custom templates use {flag_emoji1} and friends instead
+    pub flags: bool,
+}
+
+impl Default for TemplateSettings {
+    fn default() -> Self {
+        TemplateSettings {
+            tooltip: DEFAULT_TOOLTIP_TEMPLATE.to_string(),
+            tray_item: DEFAULT_MATCH_TEMPLATE.to_string(),
+            notification: DEFAULT_NOTIFICATION_TEMPLATE.to_string(),
+            cli: DEFAULT_MATCH_TEMPLATE.to_string(),
+            flags: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    // IANA timezone for match start times, e.g. "Europe/Berlin"; unset uses the system timezone
+    pub timezone: Option<String>,
+}
+
+// Teams and events followed for the calendar feed and reminders; with both empty nothing is followed
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct FollowSettings {
+    // Team names, compared case-insensitively
+    pub teams: Vec<String>,
+    // Case-insensitive parts of event names, e.g. "Masters"
+    pub events: Vec<String>,
+}
+
+impl FollowSettings {
+    pub fn follows(&self, segment: &MatchSegment) -> bool {
+        self.follows_match(&segment.team1, &segment.team2, &segment.match_event)
+    }
+
+    // Same as `follows`, for matches only known by their teams and event name
+    pub fn follows_match(&self, team1: &str, team2: &str, event: &str) -> bool {
+        let event = event.to_lowercase();
+        self.teams
+            .iter()
+            .any(|team| team.eq_ignore_ascii_case(team1) || team.eq_ignore_ascii_case(team2))
+            || self
+            .events
+            .iter()
+            .any(|name| !name.trim().is_empty() && event.contains(&name.trim().to_lowercase()))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct CalendarSettings {
+    // Reminders added to each calendar event, in minutes before the start
+    pub alarm_minutes: Vec<u32>,
+    // Length of calendar events; the API has no end time
+    pub match_duration_minutes: u32,
+}
+
+impl Default for CalendarSettings {
+    fn default() -> Self {
+        CalendarSettings {
+            alarm_minutes: vec![15],
+            match_duration_minutes: 120,
+        }
+    }
+}
+
+// Notifications before followed matches start; off unless enabled
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    pub minutes_before: u32,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        ReminderSettings {
+            enabled: false,
+            minutes_before: 15,
+        }
+    }
+}
+
+// Notifications when a followed team is in a new headline; off unless enabled
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct NewsSettings {
+    pub notify: bool,
+}
+
+// Which matches the tray and window show: a built-in preset (see filters.rs) plus custom rules
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct FilterSettings {
+    pub preset: Option<String>,
+    pub rules: Vec<FilterRule>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Settings {
+    pub shortcuts: ShortcutSettings,
+    pub scoreboard: ScoreboardSettings,
+    pub server: ServerSettings,
+    pub webhooks: WebhookSettings,
+    pub templates: TemplateSettings,
+    pub schedule: ScheduleSettings,
+    pub follow: FollowSettings,
+    pub calendar: CalendarSettings,
+    pub reminders: ReminderSettings,
+    pub news: NewsSettings,
+    pub filters: FilterSettings,
+}
+
+// Current settings and where they are persisted
+pub struct SettingsState {
+    pub settings: Mutex<Settings>,
+    path: Option<PathBuf>,
+    // Templates are compiled once at load time
+    templates: Templates,
+    // Timezone start times are shown in, from `settings.schedule`
+    display_zone: DisplayZone,
+    // Compiled from `settings.filters`, replaced when they change
+    filter: RwLock<MatchFilter>,
+    // Problems found while loading, reported to the UI
+    errors: Vec<String>,
+}
+
+impl SettingsState {
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let (settings, mut errors) = match &path {
+            Some(path) => load_settings(path),
+            None => (Settings::default(), Vec::new()),
+        };
+
+        let (templates, template_errors) = Templates::compile(&settings.templates);
+        errors.extend(template_errors);
+        let display_zone = DisplayZone::parse(settings.schedule.timezone.as_deref()).unwrap_or_else(|e| {
+            errors.push(e);
+            DisplayZone::Local
+        });
+        let filter = MatchFilter::compile(&settings.filters).unwrap_or_else(|e| {
+            errors.push(e);
+            MatchFilter::default()
+        });
+        for error in &errors {
+            eprintln!("Settings: {}", error);
+        }
+
+        SettingsState {
+            settings: Mutex::new(settings),
+            path,
+            templates,
+            display_zone,
+            filter: RwLock::new(filter),
+            errors,
+        }
+    }
+
+    pub fn filter(&self) -> MatchFilter {
+        self.filter.read().unwrap().clone()
+    }
+
+    // Validate, save and apply new filter settings
+    pub fn set_filters(&self, filters: FilterSettings) -> Result<Settings, String> {
+        let filter = MatchFilter::compile(&filters)?;
+        let settings = self.update(|s| s.filters = filters)?;
+        *self.filter.write().unwrap() = filter;
+        Ok(settings)
+    }
+
+    pub fn templates(&self) -> &Templates {
+        &self.templates
+    }
+
+    pub fn display_zone(&self) -> DisplayZone {
+        self.display_zone
+    }
+
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    // Apply a change to the settings and write them back to disk
+    pub fn update<F: FnOnce(&mut Settings)>(&self, f: F) -> Result<Settings, String> {
+        let mut settings = self.settings.lock().unwrap();
+        f(&mut settings);
+        if let Some(path) = &self.path {
+            save_settings(path, &settings)?;
+        }
+        Ok(settings.clone())
+    }
+}
+
+// Settings location for the headless CLI, which has no Tauri path resolver
+pub fn cli_settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join(SETTINGS_FILE))
+}
+
+// A file in the app's data directory, for the CLI to read what the app has cached
+pub fn cli_data_path(file: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(file))
+}
+
+// A missing or unreadable settings file falls back to defaults rather than failing startup
+fn load_settings(path: &PathBuf) -> (Settings, Vec<String>) {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return (Settings::default(), Vec::new()),
+    };
+
+    match serde_json::from_str(&contents) {
+        Ok(settings) => (settings, Vec::new()),
+        Err(e) => {
+            let error = format!("Error parsing {}: {}", path.display(), e);
+            (Settings::default(), vec![error])
+        }
+    }
+}
+
+fn save_settings(path: &PathBuf, settings: &Settings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, contents).map_err(|e| e.to_string())
+}
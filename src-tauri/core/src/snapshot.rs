@@ -0,0 +1,246 @@
+use crate::{MatchSegment, PolymarketOdds};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+// Buffered events per subscriber before slow consumers start missing events
+const EVENT_BUFFER: usize = 256;
+
+// Latest state fetched by the poller, shared with the tray, windows and local server
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MatchSnapshot {
+    pub updated_at: i64,
+    pub matches: Vec<MatchSegment>,
+    // Polymarket odds keyed by match id
+    pub odds: HashMap<String, PolymarketOdds>,
+    // Scheduled matches that have not started yet, soonest first
+    pub upcoming: Vec<MatchSegment>,
+    // When the poller found the network down; `None` while online
+    pub offline_since: Option<i64>,
+}
+
+// Changes between two consecutive snapshots
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MatchEvent {
+    MatchStarted {
+        match_id: String,
+        team1: String,
+        team2: String,
+    },
+    MapStarted {
+        match_id: String,
+        map: String,
+        map_number: String,
+    },
+    RoundsChanged {
+        match_id: String,
+        rounds1: u32,
+        rounds2: u32,
+    },
+    MapWon {
+        match_id: String,
+        team: String,
+        map: String,
+        score1: String,
+        score2: String,
+    },
+    // The match left the live feed; carries the last state seen
+    MatchEnded {
+        match_id: String,
+        last_seen: Box<MatchSegment>,
+    },
+    OddsChanged {
+        match_id: String,
+        team1_odds: Option<f64>,
+        team2_odds: Option<f64>,
+        previous_team1_odds: Option<f64>,
+        previous_team2_odds: Option<f64>,
+    },
+    // The poller found the network down; the snapshot keeps the last matches seen
+    Offline {
+        since: i64,
+    },
+    // The network is back after being offline
+    Online,
+}
+
+fn parse_score(value: &str) -> u32 {
+    value.trim().parse().unwrap_or(0)
+}
+
+// Rounds won on the current map (CT + T halves)
+pub fn map_rounds(segment: &MatchSegment) -> (u32, u32) {
+    (
+        parse_score(&segment.team1_round_ct) + parse_score(&segment.team1_round_t),
+        parse_score(&segment.team2_round_ct) + parse_score(&segment.team2_round_t),
+    )
+}
+
+pub fn diff_matches(old: &[MatchSegment], new: &[MatchSegment]) -> Vec<MatchEvent> {
+    let mut events = Vec::new();
+    let previous: HashMap<&str, &MatchSegment> =
+        old.iter().map(|m| (m.match_id.as_str(), m)).collect();
+
+    for segment in new {
+        let match_id = segment.match_id.clone();
+        let Some(before) = previous.get(segment.match_id.as_str()) else {
+            events.push(MatchEvent::MatchStarted {
+                match_id,
+                team1: segment.team1.clone(),
+                team2: segment.team2.clone(),
+            });
+            continue;
+        };
+
+        // A higher series score means the previous map was won
+        let (old1, old2) = (parse_score(&before.score1), parse_score(&before.score2));
+        let (new1, new2) = (parse_score(&segment.score1), parse_score(&segment.score2));
+        if new1 > old1 || new2 > old2 {
+            let team = if new1 > old1 { &segment.team1 } else { &segment.team2 };
+            events.push(MatchEvent::MapWon {
+                match_id: match_id.clone(),
+                team: team.clone(),
+                map: before.current_map.clone(),
+                score1: segment.score1.clone(),
+                score2: segment.score2.clone(),
+            });
+        }
+
+        if segment.current_map != before.current_map || segment.map_number != before.map_number {
+            events.push(MatchEvent::MapStarted {
+                match_id: match_id.clone(),
+                map: segment.current_map.clone(),
+                map_number: segment.map_number.clone(),
+            });
+        }
+
+        let rounds = map_rounds(segment);
+        if rounds != map_rounds(before) {
+            events.push(MatchEvent::RoundsChanged {
+                match_id,
+                rounds1: rounds.0,
+                rounds2: rounds.1,
+            });
+        }
+    }
+
+    let current: Vec<&str> = new.iter().map(|m| m.match_id.as_str()).collect();
+    for before in old {
+        if !current.contains(&before.match_id.as_str()) {
+            events.push(MatchEvent::MatchEnded {
+                match_id: before.match_id.clone(),
+                last_seen: Box::new(before.clone()),
+            });
+        }
+    }
+
+    events
+}
+
+pub fn diff_odds(
+    old: &HashMap<String, PolymarketOdds>,
+    new: &HashMap<String, PolymarketOdds>,
+) -> Vec<MatchEvent> {
+    let mut events = Vec::new();
+    for (match_id, odds) in new {
+        let (previous_team1_odds, previous_team2_odds) = old
+            .get(match_id)
+            .map(|o| (o.team1_odds, o.team2_odds))
+            .unwrap_or((None, None));
+        if odds.team1_odds != previous_team1_odds || odds.team2_odds != previous_team2_odds {
+            events.push(MatchEvent::OddsChanged {
+                match_id: match_id.clone(),
+                team1_odds: odds.team1_odds,
+                team2_odds: odds.team2_odds,
+                previous_team1_odds,
+                previous_team2_odds,
+            });
+        }
+    }
+    events
+}
+
+pub struct MatchStore {
+    snapshot: RwLock<MatchSnapshot>,
+    events: broadcast::Sender<MatchEvent>,
+}
+
+impl Default for MatchStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatchStore {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        MatchStore {
+            snapshot: RwLock::new(MatchSnapshot::default()),
+            events,
+        }
+    }
+
+    pub fn snapshot(&self) -> MatchSnapshot {
+        self.snapshot.read().unwrap().clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MatchEvent> {
+        self.events.subscribe()
+    }
+
+    // Replace the snapshot and broadcast what changed
+    pub fn update(
+        &self,
+        matches: Vec<MatchSegment>,
+        odds: HashMap<String, PolymarketOdds>,
+    ) -> Vec<MatchEvent> {
+        let mut snapshot = self.snapshot.write().unwrap();
+        let mut events = diff_matches(&snapshot.matches, &matches);
+        events.extend(diff_odds(&snapshot.odds, &odds));
+
+        // The first poll only establishes a baseline; matches already in progress
+        // at startup are not reported as newly started
+        if snapshot.updated_at == 0 {
+            events.clear();
+        }
+
+        snapshot.updated_at = chrono::Utc::now().timestamp();
+        snapshot.matches = matches;
+        snapshot.odds = odds;
+        drop(snapshot);
+
+        for event in &events {
+            // No receivers is fine; nobody is listening yet
+            let _ = self.events.send(event.clone());
+        }
+        events
+    }
+
+    // Upcoming matches don't produce events; they are only shown
+    pub fn set_upcoming(&self, mut upcoming: Vec<MatchSegment>) {
+        upcoming.sort_by_key(|m| m.starts_at.unwrap_or(i64::MAX));
+        self.snapshot.write().unwrap().upcoming = upcoming;
+    }
+
+    // Mark the network as down (since the given time) or back up, broadcasting the change.
+    // The last matches are kept so they can still be shown.
+    pub fn set_offline(&self, since: Option<i64>) {
+        let mut snapshot = self.snapshot.write().unwrap();
+        let was_offline = snapshot.offline_since.is_some();
+        snapshot.offline_since = since;
+        drop(snapshot);
+
+        let event = match since {
+            Some(since) if !was_offline => MatchEvent::Offline { since },
+            None if was_offline => MatchEvent::Online,
+            _ => return,
+        };
+        let _ = self.events.send(event);
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.snapshot.read().unwrap().offline_since.is_some()
+    }
+}
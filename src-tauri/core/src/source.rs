@@ -0,0 +1,581 @@
+// Where match data comes from: the live APIs, built-in test data, or a recorded session.
+//
+// A session file is JSON lines, one raw API response per line with the time it was
+// received relative to the start of the recording. Replaying it feeds the same bodies
+// through the same parsers, so diffing, notifications and tray updates behave exactly
+// as they did live.
+use crate::api::{
+    get_dummy_polymarket_odds, get_test_matches, get_test_news, get_test_rankings, get_test_results,
+    get_test_upcoming_matches, live_score_url, news_url, parse_live_matches, parse_news, parse_polymarket_odds,
+    parse_rankings, parse_results, parse_team, polymarket_search_query, polymarket_search_url, rankings_url,
+    results_url, team_url, upcoming_url, POLYMARKET_API_URL,
+    USE_TEST_DATA, VLRGG_API_URL, VLR_URL,
+};
+use crate::models::{
+    with_derived_fields, with_result_ids, MatchResult, MatchSegment, NewsArticle, PolymarketOdds,
+    RankedTeam, TeamProfile,
+};
+use crate::schedule::market_date;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub type SourceError = Box<dyn std::error::Error + Send + Sync>;
+pub type SourceResult<T> = Result<T, SourceError>;
+
+// How long the reachability probe waits for a response
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Requests give up after these, so a stalled connection on a half-open network fails
+// (and counts as offline) instead of hanging whoever is waiting on it
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// HTTP client with connect and request timeouts, for API calls and webhooks
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("HTTP client")
+}
+
+// Whether an error means the network is down (the DNS lookup or the connection failed
+// or stalled) rather than the API answering with an error or a body that doesn't parse
+pub fn is_offline_error(error: &SourceError) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect() || e.is_timeout())
+}
+
+pub trait MatchSource: Send + Sync {
+    fn live_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>>;
+    fn upcoming_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>>;
+    // Recently completed matches, most recent first
+    fn results(&self) -> BoxFuture<'_, SourceResult<Vec<MatchResult>>>;
+    // vlr.gg ranking for a region code such as "eu" (see rankings.rs)
+    fn rankings<'a>(&'a self, region: &'a str) -> BoxFuture<'a, SourceResult<Vec<RankedTeam>>>;
+    // HTML of a vlr.gg match page, e.g. "/449012/sentinels-vs-100-thieves"
+    fn match_page<'a>(&'a self, path: &'a str) -> BoxFuture<'a, SourceResult<String>>;
+    // Profile, roster and matches of a team by its vlr.gg id
+    fn team<'a>(&'a self, team_id: &'a str) -> BoxFuture<'a, SourceResult<TeamProfile>>;
+    // Latest vlr.gg headlines, newest first
+    fn news(&self) -> BoxFuture<'_, SourceResult<Vec<NewsArticle>>>;
+    // Cheap check that the match API's host can be reached at all, used while offline
+    fn reachable(&self) -> BoxFuture<'_, bool>;
+    // `starts_at` (unix seconds) picks the market date for matches around midnight
+    fn odds<'a>(
+        &'a self,
+        team1: &'a str,
+        team2: &'a str,
+        starts_at: Option<i64>,
+    ) -> BoxFuture<'a, SourceResult<PolymarketOdds>>;
+}
+
+fn odds_date(starts_at: Option<i64>) -> NaiveDate {
+    market_date(starts_at.and_then(|t| DateTime::from_timestamp(t, 0)), Utc::now())
+}
+
+pub type SharedSource = Arc<dyn MatchSource>;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Api {
+    LiveScore,
+    Upcoming,
+    Results,
+    Rankings,
+    MatchPage,
+    Team,
+    News,
+    Polymarket,
+}
+
+// One raw API response in a session file
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecordedResponse {
+    // Milliseconds since the recording started
+    pub at_ms: u64,
+    pub recorded_at: i64,
+    pub api: Api,
+    // Search query for Polymarket requests, region for rankings, path for match pages,
+    // team id for teams, otherwise empty
+    #[serde(default)]
+    pub query: String,
+    pub status: u16,
+    pub body: String,
+}
+
+fn is_success(status: u16) -> bool {
+    (200..300).contains(&status)
+}
+
+// vlrggapi answers errors with JSON that can parse as an empty list, so the status is
+// what tells "no matches" apart from a failed request
+fn check_status(what: &str, status: u16) -> SourceResult<()> {
+    if is_success(status) {
+        Ok(())
+    } else {
+        Err(format!("{} returned {}", what, status).into())
+    }
+}
+
+// Built-in sample matches and dummy odds
+pub struct TestSource;
+
+impl MatchSource for TestSource {
+    fn live_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async { Ok(with_derived_fields(get_test_matches())) })
+    }
+
+    fn upcoming_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async { Ok(with_derived_fields(get_test_upcoming_matches())) })
+    }
+
+    fn results(&self) -> BoxFuture<'_, SourceResult<Vec<MatchResult>>> {
+        Box::pin(async { Ok(with_result_ids(get_test_results())) })
+    }
+
+    fn rankings<'a>(&'a self, _region: &'a str) -> BoxFuture<'a, SourceResult<Vec<RankedTeam>>> {
+        Box::pin(async { Ok(get_test_rankings()) })
+    }
+
+    fn match_page<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, SourceResult<String>> {
+        Box::pin(async { Err("Test data has no match pages".into()) })
+    }
+
+    fn team<'a>(&'a self, _team_id: &'a str) -> BoxFuture<'a, SourceResult<TeamProfile>> {
+        Box::pin(async { Err("Test data has no team profiles".into()) })
+    }
+
+    fn news(&self) -> BoxFuture<'_, SourceResult<Vec<NewsArticle>>> {
+        Box::pin(async { Ok(get_test_news()) })
+    }
+
+    fn reachable(&self) -> BoxFuture<'_, bool> {
+        Box::pin(async { true })
+    }
+
+    fn odds<'a>(
+        &'a self,
+        _team1: &'a str,
+        _team2: &'a str,
+        _starts_at: Option<i64>,
+    ) -> BoxFuture<'a, SourceResult<PolymarketOdds>> {
+        Box::pin(async { Ok(get_dummy_polymarket_odds()) })
+    }
+}
+
+// Appends every response to a session file
+pub struct Recorder {
+    file: Mutex<File>,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+        Ok(Recorder {
+            file: Mutex::new(file),
+            started: Instant::now(),
+        })
+    }
+
+    fn record(&self, api: Api, query: &str, status: u16, body: &str) {
+        let entry = RecordedResponse {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            recorded_at: chrono::Utc::now().timestamp(),
+            api,
+            query: query.to_string(),
+            status,
+            body: body.to_string(),
+        };
+        let result = serde_json::to_string(&entry)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                let mut file = self.file.lock().unwrap();
+                writeln!(file, "{}", line).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            eprintln!("Error recording response: {}", e);
+        }
+    }
+}
+
+// vlrggapi, vlr.gg and Polymarket over HTTP, optionally recording what they return
+pub struct LiveSource {
+    client: reqwest::Client,
+    recorder: Option<Recorder>,
+    vlrgg_url: String,
+    polymarket_url: String,
+    vlr_url: String,
+}
+
+impl LiveSource {
+    pub fn new(recorder: Option<Recorder>) -> Self {
+        Self::with_base_urls(VLRGG_API_URL, POLYMARKET_API_URL, recorder)
+    }
+
+    // Point the source at other API hosts, e.g. a local mock server
+    pub fn with_base_urls(vlrgg_url: &str, polymarket_url: &str, recorder: Option<Recorder>) -> Self {
+        LiveSource {
+            client: http_client(),
+            recorder,
+            vlrgg_url: vlrgg_url.trim_end_matches('/').to_string(),
+            polymarket_url: polymarket_url.trim_end_matches('/').to_string(),
+            vlr_url: VLR_URL.to_string(),
+        }
+    }
+
+    // Fetch match pages from another host than vlr.gg
+    pub fn with_vlr_url(mut self, vlr_url: &str) -> Self {
+        self.vlr_url = vlr_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn get(&self, url: &str) -> SourceResult<(u16, String)> {
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", "Valorant-Menubar-App")
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        Ok((status, response.text().await?))
+    }
+}
+
+impl MatchSource for LiveSource {
+    fn live_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&live_score_url(&self.vlrgg_url)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::LiveScore, "", status, &body);
+            }
+            check_status("Live scores", status)?;
+            Ok(parse_live_matches(&body)?)
+        })
+    }
+
+    fn upcoming_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&upcoming_url(&self.vlrgg_url)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::Upcoming, "", status, &body);
+            }
+            check_status("Upcoming matches", status)?;
+            Ok(parse_live_matches(&body)?)
+        })
+    }
+
+    fn results(&self) -> BoxFuture<'_, SourceResult<Vec<MatchResult>>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&results_url(&self.vlrgg_url)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::Results, "", status, &body);
+            }
+            check_status("Results", status)?;
+            Ok(parse_results(&body)?)
+        })
+    }
+
+    fn rankings<'a>(&'a self, region: &'a str) -> BoxFuture<'a, SourceResult<Vec<RankedTeam>>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&rankings_url(&self.vlrgg_url, region)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::Rankings, region, status, &body);
+            }
+            check_status(&format!("Rankings for {}", region), status)?;
+            Ok(parse_rankings(&body)?)
+        })
+    }
+
+    fn match_page<'a>(&'a self, path: &'a str) -> BoxFuture<'a, SourceResult<String>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&format!("{}{}", self.vlr_url, path)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::MatchPage, path, status, &body);
+            }
+            // Error pages are HTML too, so the status is all that tells them apart
+            check_status(&format!("Match page {}", path), status)?;
+            Ok(body)
+        })
+    }
+
+    fn team<'a>(&'a self, team_id: &'a str) -> BoxFuture<'a, SourceResult<TeamProfile>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&team_url(&self.vlrgg_url, team_id)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::Team, team_id, status, &body);
+            }
+            check_status(&format!("Team {}", team_id), status)?;
+            Ok(parse_team(&body)?.ok_or_else(|| format!("No team with id {}", team_id))?)
+        })
+    }
+
+    fn news(&self) -> BoxFuture<'_, SourceResult<Vec<NewsArticle>>> {
+        Box::pin(async move {
+            let (status, body) = self.get(&news_url(&self.vlrgg_url)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::News, "", status, &body);
+            }
+            check_status("News", status)?;
+            Ok(parse_news(&body)?)
+        })
+    }
+
+    // A HEAD request to the API host through the same client as the polls, so proxy
+    // settings apply. Any response, even an error status, means the host can be reached.
+    fn reachable(&self) -> BoxFuture<'_, bool> {
+        Box::pin(async move {
+            let probe = self
+                .client
+                .head(&self.vlrgg_url)
+                .header("User-Agent", "Valorant-Menubar-App")
+                .timeout(PROBE_TIMEOUT)
+                .send();
+            probe.await.is_ok()
+        })
+    }
+
+    fn odds<'a>(
+        &'a self,
+        team1: &'a str,
+        team2: &'a str,
+        starts_at: Option<i64>,
+    ) -> BoxFuture<'a, SourceResult<PolymarketOdds>> {
+        Box::pin(async move {
+            let query = polymarket_search_query(team1, team2);
+            let (status, body) = self.get(&polymarket_search_url(&self.polymarket_url, &query)).await?;
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Api::Polymarket, &query, status, &body);
+            }
+            let body = is_success(status).then_some(body.as_str());
+            Ok(parse_polymarket_odds(team1, team2, odds_date(starts_at), body)?)
+        })
+    }
+}
+
+// Plays a session file back, `speed` times faster than it was recorded.
+// Each request returns the latest response recorded up to the current replay position;
+// after the end of the session the last state is kept.
+pub struct ReplaySource {
+    responses: Vec<RecordedResponse>,
+    started: Instant,
+    speed: f64,
+    finished: AtomicBool,
+}
+
+impl ReplaySource {
+    pub fn load(path: &Path, speed: f64) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let responses = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                serde_json::from_str::<RecordedResponse>(line)
+                    .map_err(|e| format!("{} line {}: {}", path.display(), number + 1, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(responses, speed))
+    }
+
+    pub fn new(mut responses: Vec<RecordedResponse>, speed: f64) -> Self {
+        responses.sort_by_key(|r| r.at_ms);
+        // Start the replay at the first response rather than when recording began
+        if let Some(first) = responses.first().map(|r| r.at_ms) {
+            for response in &mut responses {
+                response.at_ms -= first;
+            }
+        }
+        ReplaySource {
+            responses,
+            started: Instant::now(),
+            speed,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    fn position_ms(&self) -> u64 {
+        (self.started.elapsed().as_millis() as f64 * self.speed) as u64
+    }
+
+    // Latest response matching `wanted` at the current position, or the first one
+    // recorded if the position has not reached it yet
+    fn current<F>(&self, wanted: F) -> Option<&RecordedResponse>
+    where
+        F: Fn(&RecordedResponse) -> bool,
+    {
+        let position = self.position_ms();
+        let mut matching = self.responses.iter().filter(|r| wanted(r)).peekable();
+        let first = matching.peek().copied();
+        matching.take_while(|r| r.at_ms <= position).last().or(first)
+    }
+}
+
+impl MatchSource for ReplaySource {
+    fn live_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async move {
+            let last = self.responses.iter().rev().find(|r| r.api == Api::LiveScore);
+            let response = self
+                .current(|r| r.api == Api::LiveScore)
+                .ok_or("Session has no live_score responses")?;
+            if last.is_some_and(|last| std::ptr::eq(last, response))
+                && !self.finished.swap(true, Ordering::Relaxed)
+            {
+                eprintln!("Replay finished; keeping the last recorded state");
+            }
+            check_status("Live scores", response.status)?;
+            Ok(parse_live_matches(&response.body)?)
+        })
+    }
+
+    // Sessions recorded before upcoming matches were fetched have none
+    fn upcoming_matches(&self) -> BoxFuture<'_, SourceResult<Vec<MatchSegment>>> {
+        Box::pin(async move {
+            match self.current(|r| r.api == Api::Upcoming) {
+                Some(response) => {
+                    check_status("Upcoming matches", response.status)?;
+                    Ok(parse_live_matches(&response.body)?)
+                }
+                None => Ok(Vec::new()),
+            }
+        })
+    }
+
+    fn results(&self) -> BoxFuture<'_, SourceResult<Vec<MatchResult>>> {
+        Box::pin(async move {
+            match self.current(|r| r.api == Api::Results) {
+                Some(response) => {
+                    check_status("Results", response.status)?;
+                    Ok(parse_results(&response.body)?)
+                }
+                None => Ok(Vec::new()),
+            }
+        })
+    }
+
+    fn rankings<'a>(&'a self, region: &'a str) -> BoxFuture<'a, SourceResult<Vec<RankedTeam>>> {
+        Box::pin(async move {
+            let response = self
+                .current(|r| r.api == Api::Rankings && r.query == region)
+                .ok_or_else(|| format!("Session has no rankings for {}", region))?;
+            check_status(&format!("Rankings for {}", region), response.status)?;
+            Ok(parse_rankings(&response.body)?)
+        })
+    }
+
+    fn match_page<'a>(&'a self, path: &'a str) -> BoxFuture<'a, SourceResult<String>> {
+        Box::pin(async move {
+            let response = self
+                .current(|r| r.api == Api::MatchPage && r.query == path)
+                .ok_or_else(|| format!("Session has no match page {}", path))?;
+            check_status(&format!("Match page {}", path), response.status)?;
+            Ok(response.body.clone())
+        })
+    }
+
+    fn team<'a>(&'a self, team_id: &'a str) -> BoxFuture<'a, SourceResult<TeamProfile>> {
+        Box::pin(async move {
+            let response = self
+                .current(|r| r.api == Api::Team && r.query == team_id)
+                .ok_or_else(|| format!("Session has no team {}", team_id))?;
+            check_status(&format!("Team {}", team_id), response.status)?;
+            Ok(parse_team(&response.body)?.ok_or_else(|| format!("No team with id {}", team_id))?)
+        })
+    }
+
+    fn news(&self) -> BoxFuture<'_, SourceResult<Vec<NewsArticle>>> {
+        Box::pin(async move {
+            match self.current(|r| r.api == Api::News) {
+                Some(response) => {
+                    check_status("News", response.status)?;
+                    Ok(parse_news(&response.body)?)
+                }
+                None => Ok(Vec::new()),
+            }
+        })
+    }
+
+    fn reachable(&self) -> BoxFuture<'_, bool> {
+        Box::pin(async { true })
+    }
+
+    fn odds<'a>(
+        &'a self,
+        team1: &'a str,
+        team2: &'a str,
+        starts_at: Option<i64>,
+    ) -> BoxFuture<'a, SourceResult<PolymarketOdds>> {
+        Box::pin(async move {
+            let query = polymarket_search_query(team1, team2);
+            let response = self
+                .current(|r| r.api == Api::Polymarket && r.query == query)
+                .ok_or_else(|| format!("Session has no odds for {}", query))?;
+            let body = is_success(response.status).then_some(response.body.as_str());
+            Ok(parse_polymarket_odds(team1, team2, odds_date(starts_at), body)?)
+        })
+    }
+}
+
+// How the app or CLI should get its data
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceOptions {
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub speed: f64,
+}
+
+// Replays run in real time unless a speed is given
+impl Default for SourceOptions {
+    fn default() -> Self {
+        SourceOptions {
+            record: None,
+            replay: None,
+            speed: 1.0,
+        }
+    }
+}
+
+impl SourceOptions {
+    // VALO_RECORD=<file>, VALO_REPLAY=<file> and VALO_REPLAY_SPEED=<factor>
+    pub fn from_env() -> Self {
+        let path = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        SourceOptions {
+            record: path("VALO_RECORD"),
+            replay: path("VALO_REPLAY"),
+            speed: std::env::var("VALO_REPLAY_SPEED")
+                .ok()
+                .and_then(|speed| speed.parse().ok())
+                .filter(|speed: &f64| *speed > 0.0)
+                .unwrap_or(1.0),
+        }
+    }
+}
+
+pub fn create_source(options: &SourceOptions) -> Result<SharedSource, String> {
+    if let Some(path) = &options.replay {
+        eprintln!("Replaying {} at {}x", path.display(), options.speed);
+        return Ok(Arc::new(ReplaySource::load(path, options.speed)?));
+    }
+    if USE_TEST_DATA {
+        return Ok(Arc::new(TestSource));
+    }
+    let recorder = match &options.record {
+        Some(path) => {
+            eprintln!("Recording API responses to {}", path.display());
+            Some(Recorder::create(path).map_err(|e| format!("{}: {}", path.display(), e))?)
+        }
+        None => None,
+    };
+    Ok(Arc::new(LiveSource::new(recorder)))
+}
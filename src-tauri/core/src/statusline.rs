@@ -0,0 +1,71 @@
+use crate::template::{Template, TemplateContext};
+use crate::MatchSegment;
+use serde_json::json;
+
+pub const DEFAULT_STATUS_FORMAT: &str = "{team1} {score1}-{score2} {team2} [{map}]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusProtocol {
+    Waybar,
+    I3bar,
+    Plain,
+}
+
+impl StatusProtocol {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "waybar" => Some(StatusProtocol::Waybar),
+            "i3bar" | "i3status" => Some(StatusProtocol::I3bar),
+            "plain" | "polybar" => Some(StatusProtocol::Plain),
+            _ => None,
+        }
+    }
+}
+
+fn tooltip(matches: &[MatchSegment], template: &Template) -> String {
+    matches
+        .iter()
+        .map(|segment| crate::format::format_match_text(segment, template))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Header lines the i3bar protocol expects before the first status line
+pub fn i3bar_header() -> [&'static str; 2] {
+    ["{\"version\":1}", "["]
+}
+
+// One status line for the featured (first) match in the given protocol. Waybar also
+// gets every match as a tooltip, one line each from `tooltip_template`.
+pub fn render_status(
+    protocol: StatusProtocol,
+    template: &Template,
+    tooltip_template: &Template,
+    matches: &[MatchSegment],
+) -> String {
+    let featured = matches.first();
+    let text = featured
+        .map(|segment| template.render(&TemplateContext::new(segment, None)))
+        .unwrap_or_default();
+
+    match protocol {
+        // Waybar hides custom modules with empty text; the class allows live styling
+        StatusProtocol::Waybar => json!({
+            "text": text,
+            "tooltip": tooltip(matches, tooltip_template),
+            "class": if featured.is_some() { "live" } else { "idle" },
+            "alt": if featured.is_some() { "live" } else { "idle" },
+        })
+        .to_string(),
+        // i3bar reads an endless JSON array, one block list per line
+        StatusProtocol::I3bar => {
+            let blocks = if featured.is_some() {
+                json!([{ "name": "valorant", "full_text": text }])
+            } else {
+                json!([])
+            };
+            format!("{},", blocks)
+        }
+        StatusProtocol::Plain => text,
+    }
+}
@@ -0,0 +1,339 @@
+// Team registry and team profiles.
+//
+// The registry knows each team's vlr.gg id, tag and logo. It starts from the teams
+// built in below and learns logos from the matches and rankings the app sees. The feeds
+// carry no team ids, so the id of a team clicked on a match card is learned from the
+// team links on that match's vlr.gg page the first time it is needed. Profiles come
+// from the vlrggapi team endpoint, are cached for a while, and have their logo URLs
+// resolved and their schedule completed from the local upcoming matches.
+use crate::api::{match_page_path, VLR_URL};
+use crate::format::team_tag;
+use crate::models::{team_to_slug, MatchSegment, RankedTeam, TeamMatch, TeamProfile};
+use crate::persist::{load_json, save_json};
+use crate::source::{MatchSource, SourceResult};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// How long a fetched profile is served before it is fetched again
+pub const PROFILE_MAX_AGE_SECS: i64 = 3600;
+// Latest results counted in a team's form
+const FORM_LENGTH: usize = 5;
+
+// vlr.gg ids of well-known teams
+const KNOWN_TEAMS: &[(&str, &str)] = &[
+    ("2", "Sentinels"),
+    ("120", "100 Thieves"),
+    ("188", "Cloud9"),
+    ("474", "Team Liquid"),
+    ("624", "Paper Rex"),
+    ("1034", "NRG"),
+    ("2355", "KRÜ Esports"),
+    ("2593", "Fnatic"),
+    ("5248", "Evil Geniuses"),
+    ("6961", "LOUD"),
+    ("7035", "KOI"),
+    ("7386", "MIBR"),
+    ("8185", "DRX"),
+    ("11058", "G2 Esports"),
+];
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TeamEntry {
+    // vlr.gg team id, when known
+    pub id: Option<String>,
+    pub name: String,
+    pub tag: String,
+    // Absolute logo URL, empty when none has been seen
+    pub logo: String,
+}
+
+// vlr.gg logos come as absolute, protocol-relative ("//owcdn.net/...") or site-relative
+// ("/img/vlr/tmp/vlr.png") URLs
+pub fn resolve_logo_url(logo: &str) -> String {
+    let logo = logo.trim();
+    if logo.is_empty() || logo.starts_with("http://") || logo.starts_with("https://") {
+        logo.to_string()
+    } else if let Some(rest) = logo.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else if logo.starts_with('/') {
+        format!("{}{}", VLR_URL, logo)
+    } else {
+        format!("{}/{}", VLR_URL, logo)
+    }
+}
+
+// Team ids and names linked from a vlr.gg match page header, team 1 first
+// (`<a class="match-header-link" href="/team/2/sentinels">`)
+pub fn parse_team_links(html: &str) -> Vec<(String, String)> {
+    let document = Html::parse_document(html);
+    let links = Selector::parse("a.match-header-link").expect("valid selector");
+    let names = Selector::parse(".wf-title-med").expect("valid selector");
+    document
+        .select(&links)
+        .filter_map(|link| {
+            let href = link.value().attr("href")?;
+            let id = href.split("/team/").nth(1)?.split('/').next()?;
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let name = link.select(&names).next()?.text().collect::<String>().trim().to_string();
+            (!name.is_empty()).then(|| (id.to_string(), name))
+        })
+        .collect()
+}
+
+// Teams by slug and where what was learned about them is persisted
+pub struct TeamRegistry {
+    path: Option<PathBuf>,
+    teams: Mutex<HashMap<String, TeamEntry>>,
+}
+
+impl TeamRegistry {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut teams: HashMap<String, TeamEntry> = KNOWN_TEAMS
+            .iter()
+            .map(|(id, name)| {
+                let entry = TeamEntry {
+                    id: Some(id.to_string()),
+                    name: name.to_string(),
+                    tag: team_tag(name),
+                    logo: String::new(),
+                };
+                (team_to_slug(name), entry)
+            })
+            .collect();
+        let saved: HashMap<String, TeamEntry> = load_json(path.as_deref());
+        teams.extend(saved);
+        TeamRegistry {
+            path,
+            teams: Mutex::new(teams),
+        }
+    }
+
+    fn save(&self, teams: &HashMap<String, TeamEntry>) {
+        save_json(self.path.as_deref(), teams, "team registry");
+    }
+
+    // Add or update a team; returns whether anything changed
+    fn learn(teams: &mut HashMap<String, TeamEntry>, id: Option<&str>, name: &str, tag: &str, logo: &str) -> bool {
+        if name.trim().is_empty() {
+            return false;
+        }
+        let entry = teams.entry(team_to_slug(name)).or_insert_with(|| TeamEntry {
+            id: None,
+            name: name.to_string(),
+            tag: team_tag(name),
+            logo: String::new(),
+        });
+        let before = entry.clone();
+        if let Some(id) = id.filter(|id| !id.is_empty()) {
+            entry.id = Some(id.to_string());
+        }
+        if !tag.trim().is_empty() {
+            entry.tag = tag.trim().to_string();
+        }
+        if !logo.trim().is_empty() {
+            entry.logo = resolve_logo_url(logo);
+        }
+        *entry != before
+    }
+
+    pub fn learn_matches(&self, matches: &[MatchSegment]) {
+        let mut teams = self.teams.lock().unwrap();
+        let mut changed = false;
+        for segment in matches {
+            changed |= Self::learn(&mut teams, None, &segment.team1, "", &segment.team1_logo);
+            changed |= Self::learn(&mut teams, None, &segment.team2, "", &segment.team2_logo);
+        }
+        if changed {
+            self.save(&teams);
+        }
+    }
+
+    pub fn learn_rankings(&self, ranked: &[RankedTeam]) {
+        let mut teams = self.teams.lock().unwrap();
+        let mut changed = false;
+        for team in ranked {
+            changed |= Self::learn(&mut teams, None, &team.team, "", &team.logo);
+        }
+        if changed {
+            self.save(&teams);
+        }
+    }
+
+    pub fn learn_profile(&self, profile: &TeamProfile) {
+        let mut teams = self.teams.lock().unwrap();
+        if Self::learn(&mut teams, Some(&profile.id), &profile.name, &profile.tag, &profile.logo) {
+            self.save(&teams);
+        }
+    }
+
+    // Learn both teams' ids from the page of `segment`. The names on the page can differ
+    // from the feed's, so the feed's names get the ids too, in header order.
+    pub fn learn_match_page(&self, segment: &MatchSegment, html: &str) {
+        let links = parse_team_links(html);
+        let mut teams = self.teams.lock().unwrap();
+        let mut changed = false;
+        for (id, name) in &links {
+            changed |= Self::learn(&mut teams, Some(id), name, "", "");
+        }
+        if let [(id1, _), (id2, _)] = links.as_slice() {
+            changed |= Self::learn(&mut teams, Some(id1), &segment.team1, "", "");
+            changed |= Self::learn(&mut teams, Some(id2), &segment.team2, "", "");
+        }
+        if changed {
+            self.save(&teams);
+        }
+    }
+
+    // Find a team's id on the page of a match it plays in, for teams not seen before
+    pub async fn discover_id(&self, source: &dyn MatchSource, team: &str, matches: &[MatchSegment]) -> Option<String> {
+        let slug = team_to_slug(team);
+        let segment = matches
+            .iter()
+            .find(|m| team_to_slug(&m.team1) == slug || team_to_slug(&m.team2) == slug)?;
+        let path = match_page_path(&segment.match_page)?;
+        match source.match_page(&path).await {
+            Ok(html) => self.learn_match_page(segment, &html),
+            Err(e) => eprintln!("Error fetching {} to find team ids: {}", path, e),
+        }
+        self.find(team).and_then(|entry| entry.id)
+    }
+
+    pub fn find(&self, name: &str) -> Option<TeamEntry> {
+        self.teams.lock().unwrap().get(&team_to_slug(name)).cloned()
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<TeamEntry> {
+        let teams = self.teams.lock().unwrap();
+        teams.values().find(|t| t.id.as_deref() == Some(id)).cloned()
+    }
+
+    // vlr.gg id for a team given by id or by name
+    pub fn resolve_id(&self, team: &str) -> Option<String> {
+        let team = team.trim();
+        if !team.is_empty() && team.chars().all(|c| c.is_ascii_digit()) {
+            return Some(team.to_string());
+        }
+        self.find(team).and_then(|entry| entry.id)
+    }
+}
+
+// "WWLWL" from the latest results, most recent first
+pub fn team_form(recent: &[TeamMatch]) -> String {
+    recent
+        .iter()
+        .filter_map(|m| match m.result.to_lowercase().as_str() {
+            "win" | "w" => Some('W'),
+            "loss" | "l" => Some('L'),
+            _ => None,
+        })
+        .take(FORM_LENGTH)
+        .collect()
+}
+
+// Add upcoming matches the app knows about that the team endpoint didn't list
+pub fn with_local_upcoming(mut profile: TeamProfile, upcoming: &[MatchSegment]) -> TeamProfile {
+    let slug = team_to_slug(&profile.name);
+    let listed: Vec<Option<String>> = profile
+        .upcoming_matches
+        .iter()
+        .map(|m| match_page_path(&m.match_page))
+        .collect();
+    for segment in upcoming {
+        let opponent = if team_to_slug(&segment.team1) == slug {
+            &segment.team2
+        } else if team_to_slug(&segment.team2) == slug {
+            &segment.team1
+        } else {
+            continue;
+        };
+        let page = match_page_path(&segment.match_page);
+        if page.is_some() && listed.contains(&page) {
+            continue;
+        }
+        profile.upcoming_matches.push(TeamMatch {
+            match_page: segment.match_page.clone(),
+            opponent: opponent.clone(),
+            score: String::new(),
+            result: String::new(),
+            event: segment.match_event.clone(),
+            date: segment.start_time.clone(),
+        });
+    }
+    profile
+}
+
+// Fetched profiles by team id
+#[derive(Default)]
+pub struct ProfileCache {
+    profiles: Mutex<HashMap<String, (i64, TeamProfile)>>,
+}
+
+impl ProfileCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Profile for a team given by id or name, fetched when not cached in the last hour.
+    // Teams without a known id are looked up on the page of one of `matches` they play
+    // in. A failed fetch falls back to the cached profile if there is one.
+    pub async fn get(
+        &self,
+        source: &dyn MatchSource,
+        registry: &TeamRegistry,
+        team: &str,
+        matches: &[MatchSegment],
+        now: i64,
+    ) -> SourceResult<TeamProfile> {
+        let id = match registry.resolve_id(team) {
+            Some(id) => id,
+            None => registry
+                .discover_id(source, team, matches)
+                .await
+                .ok_or_else(|| format!("Unknown team '{}'", team))?,
+        };
+        let cached = self.profiles.lock().unwrap().get(&id).cloned();
+        if let Some((fetched_at, profile)) = &cached {
+            if now - fetched_at < PROFILE_MAX_AGE_SECS {
+                return Ok(profile.clone());
+            }
+        }
+
+        let mut profile = match source.team(&id).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                return match cached {
+                    Some((_, profile)) => {
+                        eprintln!("Error fetching team {}, using the cached profile: {}", id, e);
+                        Ok(profile)
+                    }
+                    None => Err(e),
+                };
+            }
+        };
+        if profile.id.is_empty() {
+            profile.id = id.clone();
+        }
+        profile.logo = resolve_logo_url(&profile.logo);
+        if profile.logo.is_empty() {
+            profile.logo = registry
+                .by_id(&id)
+                .or_else(|| registry.find(&profile.name))
+                .map(|t| t.logo)
+                .unwrap_or_default();
+        }
+        if profile.tag.is_empty() {
+            profile.tag = team_tag(&profile.name);
+        }
+        if profile.form.is_empty() {
+            profile.form = team_form(&profile.recent_matches);
+        }
+        registry.learn_profile(&profile);
+        self.profiles.lock().unwrap().insert(id, (now, profile.clone()));
+        Ok(profile)
+    }
+}
@@ -0,0 +1,360 @@
+// Small template language for match text.
+//
+//   {field}              value of a field, e.g. {team1}, {score1}, {map}, {odds1}
+//   {?cond}...{/cond}    section rendered when `cond` holds (live, upcoming, odds, or a non-empty field)
+//   {!cond}...{/cond}    section rendered when `cond` does not hold
+//   {{ and }}            literal braces
+use crate::snapshot::map_rounds;
+use crate::format::team_tag;
+use crate::schedule;
+use crate::{MatchSegment, PolymarketOdds};
+use std::fmt;
+
+pub const DEFAULT_MATCH_TEMPLATE: &str =
+    "{team1} vs {team2} | {score1} - {score2}{?live} | {map}{/live}{?upcoming} | {time_until_match}{/upcoming}";
+pub const DEFAULT_TOOLTIP_TEMPLATE: &str =
+    "Valorant: {team1} vs {team2} | {score1} - {score2}{?live} | {map}{/live}{?upcoming} | {time_until_match}{/upcoming}";
+// The default match text and tooltip with each team's flag in front, used in place of
+// the defaults when flags are turned on
+pub const DEFAULT_FLAG_MATCH_TEMPLATE: &str =
+    "{?flag_emoji1}{flag_emoji1} {/flag_emoji1}{team1} vs {?flag_emoji2}{flag_emoji2} {/flag_emoji2}{team2} | {score1} - {score2}{?live} | {map}{/live}{?upcoming} | {time_until_match}{/upcoming}";
+pub const DEFAULT_FLAG_TOOLTIP_TEMPLATE: &str =
+    "Valorant: {?flag_emoji1}{flag_emoji1} {/flag_emoji1}{team1} vs {?flag_emoji2}{flag_emoji2} {/flag_emoji2}{team2} | {score1} - {score2}{?live} | {map}{/live}{?upcoming} | {time_until_match}{/upcoming}";
+pub const DEFAULT_NOTIFICATION_TEMPLATE: &str = "{team1} {score1} - {score2} {team2}";
+
+// Raw `MatchSegment` fields plus derived values
+const FIELDS: &[&str] = &[
+    "team1",
+    "team2",
+    "score1",
+    "score2",
+    "current_map",
+    "match_event",
+    "match_series",
+    "time_until_match",
+    "flag1",
+    "flag2",
+    "team1_logo",
+    "team2_logo",
+    "team1_round_ct",
+    "team1_round_t",
+    "team2_round_ct",
+    "team2_round_t",
+    "map_number",
+    "unix_timestamp",
+    "match_page",
+    "match_id",
+    "map",
+    "event",
+    "series",
+    "status",
+    "rounds1",
+    "rounds2",
+    "tag1",
+    "tag2",
+    "odds1",
+    "odds2",
+    "start_time",
+    "countdown",
+    "country1",
+    "country2",
+    "country_code1",
+    "country_code2",
+    "flag_emoji1",
+    "flag_emoji2",
+];
+
+const CONDITIONS: &[&str] = &["live", "upcoming", "odds"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    // Character (not byte) index in the template, as users count it
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Field(String),
+    Section {
+        name: String,
+        negated: bool,
+        children: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+// Values available to a template
+pub struct TemplateContext<'a> {
+    pub segment: &'a MatchSegment,
+    pub odds: Option<&'a PolymarketOdds>,
+}
+
+impl<'a> TemplateContext<'a> {
+    pub fn new(segment: &'a MatchSegment, odds: Option<&'a PolymarketOdds>) -> Self {
+        TemplateContext { segment, odds }
+    }
+
+    fn is_live(&self) -> bool {
+        self.segment.time_until_match == "LIVE"
+    }
+
+    fn value(&self, name: &str) -> String {
+        let s = self.segment;
+        let percent = |price: Option<f64>| {
+            price
+                .map(|p| format!("{:.0}%", p * 100.0))
+                .unwrap_or_default()
+        };
+        match name {
+            "team1" => s.team1.clone(),
+            "team2" => s.team2.clone(),
+            "score1" => s.score1.clone(),
+            "score2" => s.score2.clone(),
+            "current_map" => s.current_map.clone(),
+            "match_event" | "event" => s.match_event.clone(),
+            "match_series" | "series" => s.match_series.clone(),
+            "time_until_match" => s.time_until_match.clone(),
+            "flag1" => s.flag1.clone(),
+            "flag2" => s.flag2.clone(),
+            "team1_logo" => s.team1_logo.clone(),
+            "team2_logo" => s.team2_logo.clone(),
+            "team1_round_ct" => s.team1_round_ct.clone(),
+            "team1_round_t" => s.team1_round_t.clone(),
+            "team2_round_ct" => s.team2_round_ct.clone(),
+            "team2_round_t" => s.team2_round_t.clone(),
+            "map_number" => s.map_number.clone(),
+            "unix_timestamp" => s.unix_timestamp.clone(),
+            "match_page" => s.match_page.clone(),
+            "match_id" => s.match_id.clone(),
+            "map" if s.current_map.is_empty() => "Unknown Map".to_string(),
+            "map" => s.current_map.clone(),
+            "status" => s.time_until_match.clone(),
+            "rounds1" => map_rounds(s).0.to_string(),
+            "rounds2" => map_rounds(s).1.to_string(),
+            "tag1" => team_tag(&s.team1),
+            "tag2" => team_tag(&s.team2),
+            "odds1" => percent(self.odds.and_then(|o| o.team1_odds)),
+            "odds2" => percent(self.odds.and_then(|o| o.team2_odds)),
+            "start_time" => s.start_time.clone(),
+            "countdown" => s
+                .starts_at
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|start| schedule::countdown(start, chrono::Utc::now()))
+                .unwrap_or_default(),
+            "country1" => s.country1.as_ref().map(|c| c.name.clone()).unwrap_or_default(),
+            "country2" => s.country2.as_ref().map(|c| c.name.clone()).unwrap_or_default(),
+            "country_code1" => s.country1.as_ref().map(|c| c.code.clone()).unwrap_or_default(),
+            "country_code2" => s.country2.as_ref().map(|c| c.code.clone()).unwrap_or_default(),
+            "flag_emoji1" => s.country1.as_ref().map(|c| c.emoji.clone()).unwrap_or_default(),
+            "flag_emoji2" => s.country2.as_ref().map(|c| c.emoji.clone()).unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn condition(&self, name: &str) -> bool {
+        match name {
+            "live" => self.is_live(),
+            "upcoming" => !self.is_live() && !self.segment.time_until_match.is_empty(),
+            "odds" => self
+                .odds
+                .is_some_and(|o| o.team1_odds.is_some() || o.team2_odds.is_some()),
+            field => !self.value(field).is_empty(),
+        }
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Template, TemplateError> {
+        // Open sections: (name, negated, position, nodes collected so far in the parent)
+        let mut stack: Vec<(String, bool, usize, Vec<Node>)> = Vec::new();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().enumerate().peekable();
+
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, next)| *next) == Some('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek().map(|(_, next)| *next) == Some('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => {
+                    return Err(TemplateError {
+                        position,
+                        message: "Unmatched '}' (use '}}' for a literal brace)".to_string(),
+                    })
+                }
+                '{' => {
+                    let mut tag = String::new();
+                    let mut closed = false;
+                    for (_, c) in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        tag.push(c);
+                    }
+                    if !closed {
+                        return Err(TemplateError {
+                            position,
+                            message: "Unclosed '{'".to_string(),
+                        });
+                    }
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+
+                    let tag = tag.trim();
+                    if let Some(name) = tag.strip_prefix('/') {
+                        match stack.pop() {
+                            Some((open, negated, _, parent)) if open == name => {
+                                let children = std::mem::replace(&mut nodes, parent);
+                                nodes.push(Node::Section {
+                                    name: open,
+                                    negated,
+                                    children,
+                                });
+                            }
+                            Some((open, ..)) => {
+                                return Err(TemplateError {
+                                    position,
+                                    message: format!("Expected {{/{}}} but found {{/{}}}", open, name),
+                                })
+                            }
+                            None => {
+                                return Err(TemplateError {
+                                    position,
+                                    message: format!("{{/{}}} without a matching section", name),
+                                })
+                            }
+                        }
+                    } else if let Some((negated, name)) = tag
+                        .strip_prefix('?')
+                        .map(|name| (false, name))
+                        .or_else(|| tag.strip_prefix('!').map(|name| (true, name)))
+                    {
+                        if !CONDITIONS.contains(&name) && !FIELDS.contains(&name) {
+                            return Err(TemplateError {
+                                position,
+                                message: format!("Unknown condition '{}'", name),
+                            });
+                        }
+                        let parent = std::mem::take(&mut nodes);
+                        stack.push((name.to_string(), negated, position, parent));
+                    } else if FIELDS.contains(&tag) {
+                        nodes.push(Node::Field(tag.to_string()));
+                    } else {
+                        return Err(TemplateError {
+                            position,
+                            message: format!("Unknown placeholder '{{{}}}'", tag),
+                        });
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if let Some((name, _, position, _)) = stack.pop() {
+            return Err(TemplateError {
+                position,
+                message: format!("Section '{}' is never closed", name),
+            });
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+
+        Ok(Template { nodes })
+    }
+
+    pub fn render(&self, ctx: &TemplateContext) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, ctx, &mut output);
+        output
+    }
+}
+
+fn render_nodes(nodes: &[Node], ctx: &TemplateContext, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Field(name) => output.push_str(&ctx.value(name)),
+            Node::Section {
+                name,
+                negated,
+                children,
+            } => {
+                if ctx.condition(name) != *negated {
+                    render_nodes(children, ctx, output);
+                }
+            }
+        }
+    }
+}
+
+// Compiled templates for every place match text is shown
+#[derive(Debug, Clone)]
+pub struct Templates {
+    pub tooltip: Template,
+    pub tray_item: Template,
+    pub notification: Template,
+    pub cli: Template,
+}
+
+impl Templates {
+    // Compile the configured templates. Invalid ones fall back to their default
+    // and are returned as errors so they can be reported. With flags turned on,
+    // templates left at their default get the variant with team flags.
+    pub fn compile(settings: &crate::settings::TemplateSettings) -> (Templates, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut compile = |name: &str, source: &str, default: &str, flag_default: &str| {
+            let source = if settings.flags && source == default { flag_default } else { source };
+            let default = if settings.flags { flag_default } else { default };
+            Template::parse(source).unwrap_or_else(|e| {
+                errors.push(format!("Template '{}': {}", name, e));
+                Template::parse(default).expect("default template is valid")
+            })
+        };
+
+        let templates = Templates {
+            tooltip: compile(
+                "tooltip",
+                &settings.tooltip,
+                DEFAULT_TOOLTIP_TEMPLATE,
+                DEFAULT_FLAG_TOOLTIP_TEMPLATE,
+            ),
+            tray_item: compile("tray_item", &settings.tray_item, DEFAULT_MATCH_TEMPLATE, DEFAULT_FLAG_MATCH_TEMPLATE),
+            notification: compile(
+                "notification",
+                &settings.notification,
+                DEFAULT_NOTIFICATION_TEMPLATE,
+                DEFAULT_NOTIFICATION_TEMPLATE,
+            ),
+            cli: compile("cli", &settings.cli, DEFAULT_MATCH_TEMPLATE, DEFAULT_FLAG_MATCH_TEMPLATE),
+        };
+        (templates, errors)
+    }
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Templates::compile(&crate::settings::TemplateSettings::default()).0
+    }
+}
@@ -0,0 +1,471 @@
+use crate::models::{team_to_slug, MatchResult};
+use crate::persist::{load_json, save_json};
+use crate::settings::{WebhookEndpoint, WebhookFormat, WebhookSettings};
+use crate::snapshot::{MatchEvent, MatchSnapshot, MatchStore};
+use crate::source::{http_client, SharedSource};
+use crate::template::{Template, TemplateContext};
+use crate::MatchSegment;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep, Duration, Instant};
+
+// Failed deliveries are retried with exponential backoff, then dropped
+const MAX_ATTEMPTS: u32 = 8;
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 3600;
+// How long to sleep when nothing is queued
+const IDLE_WAIT: Duration = Duration::from_secs(60);
+// Ended matches are looked up in the results feed this often, for up to RESULT_WAIT_SECS
+const RESULT_CHECK: Duration = Duration::from_secs(120);
+const RESULT_WAIT_SECS: i64 = 6 * 3600;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    MatchStarted,
+    MapWon,
+    SeriesWon,
+    OddsSwing,
+}
+
+// A match event worth telling someone about, independent of payload format
+#[derive(Debug, Serialize, Clone)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub match_id: String,
+    pub title: String,
+    pub message: String,
+    pub url: Option<String>,
+    pub timestamp: i64,
+    pub event: MatchEvent,
+}
+
+fn find_match<'a>(snapshot: &'a MatchSnapshot, match_id: &str) -> Option<&'a MatchSegment> {
+    snapshot.matches.iter().find(|m| m.match_id == match_id)
+}
+
+fn match_message(template: &Template, segment: &MatchSegment, snapshot: &MatchSnapshot) -> String {
+    template.render(&TemplateContext::new(segment, snapshot.odds.get(&segment.match_id)))
+}
+
+fn page_url(segment: Option<&MatchSegment>) -> Option<String> {
+    segment
+        .map(|m| m.match_page.clone())
+        .filter(|page| !page.is_empty())
+}
+
+fn percent(price: f64) -> String {
+    format!("{:.0}%", price * 100.0)
+}
+
+// Turn a diff event into a notification, if it is one we notify about.
+// `template` renders the message body for the match involved. `MatchEnded` gives none
+// here: the series winner is only known once the results feed has the match, see
+// `EndedMatches`.
+pub fn notification_for(
+    event: &MatchEvent,
+    snapshot: &MatchSnapshot,
+    odds_swing_threshold: f64,
+    template: &Template,
+) -> Option<Notification> {
+    let (kind, match_id, title, message, url) = match event {
+        MatchEvent::MatchStarted { match_id, team1, team2 } => {
+            let segment = find_match(snapshot, match_id);
+            let message = match segment {
+                Some(segment) => match_message(template, segment, snapshot),
+                None => format!("{} vs {}", team1, team2),
+            };
+            (
+                NotificationKind::MatchStarted,
+                match_id,
+                "Match started".to_string(),
+                message,
+                page_url(segment),
+            )
+        }
+        MatchEvent::MapWon { match_id, team, map, .. } => {
+            let segment = find_match(snapshot, match_id);
+            let map = if map.is_empty() { "the map" } else { map.as_str() };
+            (
+                NotificationKind::MapWon,
+                match_id,
+                format!("{} won {}", team, map),
+                segment
+                    .map(|m| match_message(template, m, snapshot))
+                    .unwrap_or_default(),
+                page_url(segment),
+            )
+        }
+        MatchEvent::OddsChanged {
+            match_id,
+            team1_odds: Some(odds),
+            previous_team1_odds: Some(previous),
+            ..
+        } => {
+            if (odds - previous).abs() < odds_swing_threshold {
+                return None;
+            }
+            let segment = find_match(snapshot, match_id)?;
+            (
+                NotificationKind::OddsSwing,
+                match_id,
+                format!("Odds swing: {} vs {}", segment.team1, segment.team2),
+                format!(
+                    "{} {} -> {}",
+                    segment.team1,
+                    percent(*previous),
+                    percent(*odds)
+                ),
+                snapshot
+                    .odds
+                    .get(match_id)
+                    .and_then(|o| o.market_url.clone())
+                    .or_else(|| page_url(Some(segment))),
+            )
+        }
+        _ => return None,
+    };
+
+    Some(Notification {
+        kind,
+        match_id: match_id.clone(),
+        title,
+        message,
+        url,
+        timestamp: chrono::Utc::now().timestamp(),
+        event: event.clone(),
+    })
+}
+
+// SeriesWon for a match the results feed lists, with its final score on the teams as
+// the live feed named them. `None` for a series without a winner (e.g. a forfeit).
+pub fn series_notification(
+    last_seen: &MatchSegment,
+    result: &MatchResult,
+    snapshot: &MatchSnapshot,
+    template: &Template,
+) -> Option<Notification> {
+    let mut segment = last_seen.clone();
+    let swapped = team_to_slug(&result.team1) == team_to_slug(&last_seen.team2);
+    let (score1, score2) = if swapped {
+        (&result.score2, &result.score1)
+    } else {
+        (&result.score1, &result.score2)
+    };
+    segment.score1 = score1.trim().to_string();
+    segment.score2 = score2.trim().to_string();
+
+    let won1: u32 = segment.score1.parse().ok()?;
+    let won2: u32 = segment.score2.parse().ok()?;
+    let winner = match won1.cmp(&won2) {
+        std::cmp::Ordering::Greater => &segment.team1,
+        std::cmp::Ordering::Less => &segment.team2,
+        std::cmp::Ordering::Equal => return None,
+    };
+
+    Some(Notification {
+        kind: NotificationKind::SeriesWon,
+        match_id: segment.match_id.clone(),
+        title: format!("{} won the series", winner),
+        message: match_message(template, &segment, snapshot),
+        url: page_url(Some(&segment)),
+        timestamp: chrono::Utc::now().timestamp(),
+        event: MatchEvent::MatchEnded {
+            match_id: segment.match_id.clone(),
+            last_seen: Box::new(segment),
+        },
+    })
+}
+
+// Matches that left the live feed, waiting for the results feed to confirm them. The
+// last live state can be a map behind the final score, and a match can drop out of a
+// single poll without being over, so SeriesWon is only sent for confirmed results.
+#[derive(Default)]
+pub struct EndedMatches {
+    pending: Vec<(MatchSegment, i64)>,
+}
+
+impl EndedMatches {
+    pub fn push(&mut self, last_seen: MatchSegment, now: i64) {
+        self.pending.push((last_seen, now));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // SeriesWon for every pending match the results list. Matches that are live again
+    // or have waited too long are dropped without one.
+    pub fn confirm(
+        &mut self,
+        results: &[MatchResult],
+        snapshot: &MatchSnapshot,
+        template: &Template,
+        now: i64,
+    ) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+        self.pending.retain(|(last_seen, ended_at)| {
+            if find_match(snapshot, &last_seen.match_id).is_some() {
+                return false;
+            }
+            match results.iter().find(|r| r.match_id == last_seen.match_id) {
+                Some(result) => {
+                    notifications.extend(series_notification(last_seen, result, snapshot, template));
+                    false
+                }
+                None => now - ended_at < RESULT_WAIT_SECS,
+            }
+        });
+        notifications
+    }
+}
+
+fn iso_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+// Build the request body for an endpoint's payload format
+pub fn render_payload(format: WebhookFormat, notification: &Notification) -> Value {
+    match format {
+        WebhookFormat::Discord => {
+            let color = match notification.kind {
+                NotificationKind::OddsSwing => 0xFACC15,
+                _ => 0xBB1C1C,
+            };
+            let mut embed = json!({
+                "title": notification.title,
+                "description": notification.message,
+                "color": color,
+                "timestamp": iso_timestamp(notification.timestamp),
+            });
+            if let Some(url) = &notification.url {
+                embed["url"] = json!(url);
+            }
+            json!({ "embeds": [embed] })
+        }
+        WebhookFormat::Slack => {
+            let mut text = notification.message.clone();
+            if let Some(url) = &notification.url {
+                text = format!("{}\n<{}|View match>", text, url);
+            }
+            json!({
+                "text": format!("{}: {}", notification.title, notification.message),
+                "blocks": [
+                    {
+                        "type": "header",
+                        "text": { "type": "plain_text", "text": notification.title }
+                    },
+                    {
+                        "type": "section",
+                        "text": { "type": "mrkdwn", "text": text }
+                    }
+                ]
+            })
+        }
+        WebhookFormat::Json => json!(notification),
+    }
+}
+
+fn wants(endpoint: &WebhookEndpoint, kind: NotificationKind) -> bool {
+    endpoint.events.is_empty() || endpoint.events.contains(&kind)
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PendingDelivery {
+    pub url: String,
+    pub payload: Value,
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+}
+
+// Deliveries waiting to be sent, persisted so they survive restarts
+pub struct WebhookQueue {
+    path: Option<PathBuf>,
+    pub deliveries: Vec<PendingDelivery>,
+}
+
+impl WebhookQueue {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let deliveries = load_json(path.as_deref());
+        WebhookQueue { path, deliveries }
+    }
+
+    fn save(&self) {
+        save_json(self.path.as_deref(), &self.deliveries, "webhook queue");
+    }
+
+    pub fn push(&mut self, url: String, payload: Value) {
+        self.deliveries.push(PendingDelivery {
+            url,
+            payload,
+            attempts: 0,
+            next_attempt_at: 0,
+        });
+        self.save();
+    }
+
+    // Time until the next delivery is due, if any are queued
+    fn next_due_in(&self, now: i64) -> Option<Duration> {
+        self.deliveries
+            .iter()
+            .map(|d| d.next_attempt_at)
+            .min()
+            .map(|due| Duration::from_secs((due - now).max(0) as u64))
+    }
+
+    // Attempt every due delivery once, rescheduling or dropping the failures
+    pub async fn flush_due(&mut self, client: &reqwest::Client) {
+        let now = chrono::Utc::now().timestamp();
+        if !self.deliveries.iter().any(|d| d.next_attempt_at <= now) {
+            return;
+        }
+
+        let mut remaining = Vec::new();
+        for mut delivery in std::mem::take(&mut self.deliveries) {
+            if delivery.next_attempt_at > now {
+                remaining.push(delivery);
+                continue;
+            }
+            if let Err(e) = deliver(client, &delivery.url, &delivery.payload).await {
+                delivery.attempts += 1;
+                if delivery.attempts >= MAX_ATTEMPTS {
+                    eprintln!("Dropping webhook to {} after {} attempts: {}", delivery.url, delivery.attempts, e);
+                    continue;
+                }
+                let backoff = (RETRY_BASE_SECS << (delivery.attempts - 1)).min(RETRY_MAX_SECS);
+                delivery.next_attempt_at = now + backoff;
+                eprintln!("Webhook to {} failed, retrying in {}s: {}", delivery.url, backoff, e);
+                remaining.push(delivery);
+            }
+        }
+        self.deliveries = remaining;
+        self.save();
+    }
+}
+
+pub async fn deliver(client: &reqwest::Client, url: &str, payload: &Value) -> Result<(), String> {
+    let response = client
+        .post(url)
+        .header("User-Agent", "Valorant-Menubar-App")
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+// Queue a payload for every endpoint interested in this notification
+pub fn enqueue_notification(queue: &mut WebhookQueue, settings: &WebhookSettings, notification: &Notification) {
+    for endpoint in &settings.endpoints {
+        if wants(endpoint, notification.kind) {
+            queue.push(endpoint.url.clone(), render_payload(endpoint.format, notification));
+        }
+    }
+}
+
+// Queue a payload for every endpoint interested in this event
+pub fn enqueue_event(
+    queue: &mut WebhookQueue,
+    settings: &WebhookSettings,
+    template: &Template,
+    event: &MatchEvent,
+    snapshot: &MatchSnapshot,
+) {
+    if let Some(notification) = notification_for(event, snapshot, settings.odds_swing_threshold, template) {
+        enqueue_notification(queue, settings, &notification);
+    }
+}
+
+// Listen for match events and deliver them to the configured webhooks.
+// `settings` is read on every event so changes apply without a restart. Series results
+// are confirmed against `source`'s results feed before they are sent.
+pub async fn run_webhooks<F>(
+    store: Arc<MatchStore>,
+    source: SharedSource,
+    mut queue: WebhookQueue,
+    template: Template,
+    settings: F,
+) where
+    F: Fn() -> WebhookSettings,
+{
+    let client = http_client();
+    let mut events = store.subscribe();
+    let mut ended = EndedMatches::default();
+    let mut next_results_check = Instant::now();
+
+    loop {
+        // Deliveries wait while offline; the `Online` event wakes the loop to send them
+        let wait = if store.is_offline() {
+            IDLE_WAIT
+        } else {
+            if !ended.is_empty() && Instant::now() >= next_results_check {
+                next_results_check = Instant::now() + RESULT_CHECK;
+                match source.results().await {
+                    Ok(results) => {
+                        let now = chrono::Utc::now().timestamp();
+                        let settings = settings();
+                        for notification in ended.confirm(&results, &store.snapshot(), &template, now) {
+                            enqueue_notification(&mut queue, &settings, &notification);
+                        }
+                    }
+                    Err(e) => eprintln!("Error fetching results for webhooks: {}", e),
+                }
+            }
+            queue.flush_due(&client).await;
+            let now = chrono::Utc::now().timestamp();
+            let wait = queue.next_due_in(now).unwrap_or(IDLE_WAIT);
+            if ended.is_empty() {
+                wait
+            } else {
+                wait.min(next_results_check.saturating_duration_since(Instant::now()))
+            }
+        };
+
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(MatchEvent::MatchEnded { last_seen, .. }) => {
+                    // The results feed lags the live feed; give it time before the first check
+                    if ended.is_empty() {
+                        next_results_check = Instant::now() + RESULT_CHECK;
+                    }
+                    ended.push(*last_seen, chrono::Utc::now().timestamp());
+                }
+                Ok(event) => {
+                    enqueue_event(&mut queue, &settings(), &template, &event, &store.snapshot())
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            _ = sleep(wait) => {}
+        }
+    }
+}
+
+// Send a sample notification straight to an endpoint, to check its configuration
+pub async fn send_test_notification(endpoint: &WebhookEndpoint) -> Result<(), String> {
+    let notification = Notification {
+        kind: NotificationKind::MatchStarted,
+        match_id: "test".to_string(),
+        title: "Test notification".to_string(),
+        message: "Webhook is configured correctly".to_string(),
+        url: None,
+        timestamp: chrono::Utc::now().timestamp(),
+        event: MatchEvent::MatchStarted {
+            match_id: "test".to_string(),
+            team1: "Team 1".to_string(),
+            team2: "Team 2".to_string(),
+        },
+    };
+    let client = http_client();
+    deliver(&client, &endpoint.url, &render_payload(endpoint.format, &notification)).await
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use common::{temp_path, LIVE_SCORE, UPCOMING};
+use valo_core::api::parse_live_matches;
+use valo_core::calendar::{event_uid, CalendarFeed};
+use valo_core::settings::{CalendarSettings, FollowSettings};
+use valo_core::snapshot::MatchSnapshot;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 4, 4, 20, 0, 0).unwrap()
+}
+
+fn snapshot(upcoming: &str) -> MatchSnapshot {
+    MatchSnapshot {
+        updated_at: 1,
+        matches: parse_live_matches(LIVE_SCORE).unwrap(),
+        odds: Default::default(),
+        upcoming: parse_live_matches(upcoming).unwrap(),
+        offline_since: None,
+    }
+}
+
+fn follow(teams: &[&str], events: &[&str]) -> FollowSettings {
+    FollowSettings {
+        teams: teams.iter().map(|t| t.to_string()).collect(),
+        events: events.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+// Unfolded content lines of a rendered calendar
+fn lines(ics: &str) -> Vec<String> {
+    ics.replace("\r\n ", "").split("\r\n").map(str::to_string).collect()
+}
+
+#[test]
+fn exports_followed_matches_with_alarms() {
+    let feed = CalendarFeed::load(None, follow(&["nrg"], &[]), CalendarSettings::default());
+    let ics = feed.render(&snapshot(UPCOMING), now());
+    let lines = lines(&ics);
+
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(lines.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 1);
+    assert!(lines.contains(&"SUMMARY:MIBR vs NRG".to_string()));
+    assert!(lines.contains(&"DTSTART:20250405T010000Z".to_string()));
+    assert!(lines.contains(&"DTEND:20250405T030000Z".to_string()));
+    assert!(lines.contains(&"TRIGGER:-PT15M".to_string()));
+    assert!(lines.contains(&format!("UID:{}", event_uid("449019"))));
+    assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+}
+
+#[test]
+fn nothing_is_exported_without_follows() {
+    let feed = CalendarFeed::load(None, FollowSettings::default(), CalendarSettings::default());
+    let ics = feed.render(&snapshot(UPCOMING), now());
+
+    assert!(feed.followed_matches(&snapshot(UPCOMING)).is_empty());
+    assert!(!ics.contains("BEGIN:VEVENT"));
+}
+
+#[test]
+fn follows_events_and_includes_live_matches() {
+    let feed = CalendarFeed::load(None, follow(&[], &["americas"]), CalendarSettings::default());
+    let matches = feed.followed_matches(&snapshot(UPCOMING));
+    let teams: Vec<_> = matches.iter().map(|m| m.team1.as_str()).collect();
+    // Team Liquid vs Fnatic has no start time and is EMEA
+    assert_eq!(teams, ["Sentinels", "Cloud9", "MIBR"]);
+}
+
+#[test]
+fn rescheduled_matches_keep_their_uid_and_bump_the_sequence() {
+    let path = temp_path("calendar.json");
+    let feed = CalendarFeed::load(Some(path.clone()), follow(&["MIBR"], &[]), CalendarSettings::default());
+    let first = lines(&feed.render(&snapshot(UPCOMING), now()));
+    assert!(first.contains(&"SEQUENCE:0".to_string()));
+
+    // Same data again does not count as a change
+    let again = lines(&feed.render(&snapshot(UPCOMING), now()));
+    assert!(again.contains(&"SEQUENCE:0".to_string()));
+
+    // A delayed start after a restart
+    let delayed = UPCOMING.replace("2025-04-05 01:00:00", "2025-04-05 02:30:00");
+    let feed = CalendarFeed::load(Some(path.clone()), follow(&["MIBR"], &[]), CalendarSettings::default());
+    let moved = lines(&feed.render(&snapshot(&delayed), now()));
+    let _ = std::fs::remove_file(&path);
+
+    assert!(moved.contains(&format!("UID:{}", event_uid("449019"))));
+    assert!(moved.contains(&"SEQUENCE:1".to_string()));
+    assert!(moved.contains(&"DTSTART:20250405T023000Z".to_string()));
+}
+
+#[test]
+fn escapes_text_values() {
+    let upcoming = UPCOMING.replace("Regular Season: Week 3", "Playoffs, Upper; Final");
+    let settings = CalendarSettings {
+        alarm_minutes: vec![60, 5],
+        match_duration_minutes: 180,
+    };
+    let feed = CalendarFeed::load(None, follow(&["Cloud9"], &[]), settings);
+    let lines = lines(&feed.render(&snapshot(&upcoming), now()));
+
+    assert!(lines.contains(
+        &"DESCRIPTION:VCT 2025: Americas Stage 1\\nPlayoffs\\, Upper\\; Final".to_string()
+    ));
+    assert!(lines.contains(&"TRIGGER:-PT60M".to_string()));
+    assert!(lines.contains(&"TRIGGER:-PT5M".to_string()));
+    assert!(lines.contains(&"DTEND:20250406T033000Z".to_string()));
+}
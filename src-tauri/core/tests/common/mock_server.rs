@@ -0,0 +1,112 @@
+// Local HTTP server standing in for vlrggapi, Polymarket and webhook endpoints.
+// Responses are configured per path, or per "path?query" for endpoints that share
+// a path; every request is kept for assertions.
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: String,
+    pub body: String,
+}
+
+impl MockRequest {
+    // Decoded value of a query parameter
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        self.query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then(|| urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_default())
+        })
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    // Responses per path; the last one is repeated once the others are used up
+    responses: Mutex<HashMap<String, Vec<(u16, String)>>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+pub struct MockServer {
+    pub url: String,
+    state: Arc<MockState>,
+    handle: JoinHandle<()>,
+}
+
+async fn handle(State(state): State<Arc<MockState>>, method: Method, uri: Uri, body: Bytes) -> Response {
+    let path = uri.path().to_string();
+    let query = uri.query().unwrap_or_default().to_string();
+    let with_query = format!("{}?{}", path, query);
+    state.requests.lock().unwrap().push(MockRequest {
+        method,
+        path: path.clone(),
+        query,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    });
+
+    let mut responses = state.responses.lock().unwrap();
+    let key = if responses.contains_key(&with_query) { with_query } else { path };
+    let Some(queue) = responses.get_mut(&key) else {
+        return (StatusCode::NOT_FOUND, "no mock response").into_response();
+    };
+    let (status, body) = if queue.len() > 1 {
+        queue.remove(0)
+    } else {
+        queue[0].clone()
+    };
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        Self::start_at("127.0.0.1:0").await
+    }
+
+    // Listen on a given address, e.g. one a source was pointed at while nothing listened
+    pub async fn start_at(address: &str) -> Self {
+        let state = Arc::new(MockState::default());
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        MockServer { url, state, handle }
+    }
+
+    // Always answer `path` with this status and body
+    pub fn respond(&self, path: &str, status: u16, body: &str) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), vec![(status, body.to_string())]);
+    }
+
+    // Answer successive requests to `path` with each response in turn
+    pub fn respond_sequence(&self, path: &str, responses: &[(u16, &str)]) {
+        self.state.responses.lock().unwrap().insert(
+            path.to_string(),
+            responses.iter().map(|(status, body)| (*status, body.to_string())).collect(),
+        );
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,26 @@
+// Shared helpers for the integration tests: a local mock server standing in for
+// vlrggapi, Polymarket and webhook endpoints, and canned API payloads
+#![allow(dead_code)]
+
+pub mod mock_server;
+
+use std::path::PathBuf;
+
+pub const LIVE_SCORE: &str = include_str!("../fixtures/live_score.json");
+pub const LIVE_SCORE_EMPTY: &str = include_str!("../fixtures/live_score_empty.json");
+pub const UPCOMING: &str = include_str!("../fixtures/upcoming.json");
+pub const POLYMARKET_SEARCH: &str = include_str!("../fixtures/polymarket_search.json");
+pub const RESULTS: &str = include_str!("../fixtures/results.json");
+pub const RANKINGS_EU: &str = include_str!("../fixtures/rankings_eu.json");
+pub const MATCH_PAGE: &str = include_str!("../fixtures/match_page.html");
+pub const TEAM: &str = include_str!("../fixtures/team.json");
+pub const NEWS: &str = include_str!("../fixtures/news.json");
+
+// Path in the temp directory for a test's files, e.g. "ratings-restart.json"; whatever an
+// earlier run left there is removed
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("valo-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir_all(&path);
+    path
+}
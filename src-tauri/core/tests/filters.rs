@@ -0,0 +1,133 @@
+use valo_core::filters::{match_region, match_tier, FilterAction, FilterField, FilterRule, MatchFilter};
+use valo_core::settings::FilterSettings;
+use valo_core::snapshot::MatchSnapshot;
+use valo_core::{MatchSegment, PolymarketOdds};
+
+fn segment(team1: &str, team2: &str, event: &str, series: &str) -> MatchSegment {
+    let json = serde_json::json!({
+        "team1": team1,
+        "team2": team2,
+        "match_event": event,
+        "match_series": series,
+    });
+    let mut segment: MatchSegment = serde_json::from_value(json).unwrap();
+    segment.match_id = format!("{}-{}", team1, team2).to_lowercase();
+    segment
+}
+
+fn matches() -> Vec<MatchSegment> {
+    vec![
+        segment("Sentinels", "100 Thieves", "VCT 2025: Americas Stage 1", "Regular Season: Week 3"),
+        segment("Fnatic", "Team Heretics", "VCT 2025: EMEA Stage 1", "Playoffs: Upper Final"),
+        segment("Apeks", "Gentle Mates", "Challengers 2025: France Revolution", "Main Event"),
+        segment("Shopify Rebellion GC", "G2 Gozen", "VCT 2025: Game Changers Championship", "Final"),
+        segment("Paper Rex", "Fnatic", "VCT 2025: Masters Toronto", "Playoffs"),
+        segment("Ninjas", "Pirates", "Community Cup #12", "Groups"),
+    ]
+}
+
+fn rule(action: FilterAction, field: FilterField, pattern: &str) -> FilterRule {
+    FilterRule {
+        action,
+        field,
+        pattern: pattern.to_string(),
+    }
+}
+
+fn shown(settings: FilterSettings) -> Vec<String> {
+    MatchFilter::compile(&settings)
+        .unwrap()
+        .filter(matches())
+        .into_iter()
+        .map(|m| m.team1)
+        .collect()
+}
+
+fn preset(id: &str) -> FilterSettings {
+    FilterSettings {
+        preset: Some(id.to_string()),
+        rules: Vec::new(),
+    }
+}
+
+#[test]
+fn derives_region_and_tier_from_the_event() {
+    let regions: Vec<_> = matches().iter().map(match_region).collect();
+    assert_eq!(
+        regions,
+        [Some("americas"), Some("emea"), Some("emea"), Some("international"), Some("international"), None]
+    );
+    let tiers: Vec<_> = matches().iter().map(match_tier).collect();
+    assert_eq!(tiers, ["vct", "vct", "challengers", "game_changers", "vct", "other"]);
+}
+
+#[test]
+fn no_rules_show_everything() {
+    assert_eq!(shown(FilterSettings::default()).len(), 6);
+}
+
+#[test]
+fn presets_combine_tier_and_region() {
+    assert_eq!(shown(preset("vct")), ["Sentinels", "Fnatic", "Paper Rex"]);
+    assert_eq!(shown(preset("challengers_emea")), ["Apeks"]);
+    assert_eq!(shown(preset("vct_emea")), ["Fnatic"]);
+}
+
+#[test]
+fn unknown_presets_are_rejected() {
+    assert!(MatchFilter::compile(&preset("tier-9")).is_err());
+}
+
+#[test]
+fn includes_are_or_within_a_field_and_excludes_win() {
+    let settings = FilterSettings {
+        preset: None,
+        rules: vec![
+            rule(FilterAction::Include, FilterField::Team, "fnatic"),
+            rule(FilterAction::Include, FilterField::Team, "sentinels"),
+            rule(FilterAction::Exclude, FilterField::Series, "playoffs"),
+        ],
+    };
+    assert_eq!(shown(settings), ["Sentinels"]);
+}
+
+#[test]
+fn custom_rules_narrow_a_preset() {
+    let settings = FilterSettings {
+        preset: Some("vct".to_string()),
+        rules: vec![rule(FilterAction::Exclude, FilterField::Event, "masters")],
+    };
+    assert_eq!(shown(settings), ["Sentinels", "Fnatic"]);
+}
+
+#[test]
+fn wildcard_patterns_match_the_whole_value() {
+    let settings = |pattern: &str| FilterSettings {
+        preset: None,
+        rules: vec![rule(FilterAction::Include, FilterField::Event, pattern)],
+    };
+    assert_eq!(shown(settings("vct*stage 1")), ["Sentinels", "Fnatic"]);
+    assert_eq!(shown(settings("*toronto")), ["Paper Rex"]);
+    assert!(shown(settings("stage*")).is_empty());
+}
+
+#[test]
+fn filtered_snapshots_drop_odds_of_hidden_matches() {
+    let odds = |p| PolymarketOdds {
+        team1_odds: Some(p),
+        team2_odds: Some(1.0 - p),
+        market_url: None,
+    };
+    let all = matches();
+    let snapshot = MatchSnapshot {
+        updated_at: 1,
+        odds: all.iter().map(|m| (m.match_id.clone(), odds(0.5))).collect(),
+        matches: all,
+        upcoming: Vec::new(),
+        offline_since: None,
+    };
+
+    let filtered = MatchFilter::compile(&preset("vct_americas")).unwrap().apply(&snapshot);
+    assert_eq!(filtered.matches.len(), 1);
+    assert_eq!(filtered.odds.keys().collect::<Vec<_>>(), ["sentinels-100 thieves"]);
+}
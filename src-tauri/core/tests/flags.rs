@@ -0,0 +1,96 @@
+mod common;
+
+use common::{LIVE_SCORE, UPCOMING};
+use valo_core::api::parse_live_matches;
+use valo_core::flags::{country_from_flag, country_name, flag_code, flag_emoji};
+use valo_core::format::format_match_text;
+use valo_core::models::Country;
+use valo_core::settings::TemplateSettings;
+use valo_core::template::{Template, TemplateContext, Templates};
+
+#[test]
+fn flag_classes_resolve_to_iso_codes() {
+    assert_eq!(flag_code("flag_us").as_deref(), Some("US"));
+    assert_eq!(flag_code("mod-br").as_deref(), Some("BR"));
+    assert_eq!(flag_code("KR").as_deref(), Some("KR"));
+    // vlr.gg's own codes
+    assert_eq!(flag_code("flag_uk").as_deref(), Some("GB"));
+    assert_eq!(flag_code("flag_un"), None);
+    assert_eq!(flag_code(""), None);
+    assert_eq!(flag_code("flag_usa"), None);
+}
+
+#[test]
+fn countries_have_names_and_emoji() {
+    assert_eq!(flag_emoji("US"), "🇺🇸");
+    assert_eq!(flag_emoji("gb"), "🇬🇧");
+    assert_eq!(country_name("TR"), "Türkiye");
+    assert_eq!(country_name("ZZ"), "ZZ");
+    assert_eq!(
+        country_from_flag("flag_eu"),
+        Some(Country {
+            code: "EU".to_string(),
+            name: "Europe".to_string(),
+            emoji: "🇪🇺".to_string(),
+        })
+    );
+}
+
+#[test]
+fn matches_get_their_teams_countries() {
+    let upcoming = parse_live_matches(UPCOMING).unwrap();
+    assert_eq!(upcoming[1].country1.as_ref().map(|c| c.name.as_str()), Some("Brazil"));
+    assert_eq!(upcoming[1].country2.as_ref().map(|c| c.code.as_str()), Some("US"));
+
+    // Team Liquid vs Fnatic has no flags
+    let live = parse_live_matches(LIVE_SCORE).unwrap();
+    assert_eq!(live[1].country1, None);
+}
+
+#[test]
+fn templates_show_countries() {
+    let live = parse_live_matches(LIVE_SCORE).unwrap();
+    let template = Template::parse("{flag_emoji1} {team1} ({country_code1}){?country2}, {country2}{/country2}").unwrap();
+
+    assert_eq!(
+        template.render(&TemplateContext::new(&live[0], None)),
+        "🇺🇸 Sentinels (US), United States"
+    );
+    assert_eq!(template.render(&TemplateContext::new(&live[1], None)), " Team Liquid ()");
+}
+
+#[test]
+fn default_templates_show_flags_when_turned_on() {
+    let live = parse_live_matches(LIVE_SCORE).unwrap();
+    let plain = Templates::default();
+    assert_eq!(format_match_text(&live[0], &plain.cli), "Sentinels vs 100 Thieves | 1 - 0 | Bind");
+
+    let (flagged, errors) = Templates::compile(&TemplateSettings {
+        flags: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    assert_eq!(
+        format_match_text(&live[0], &flagged.cli),
+        "🇺🇸 Sentinels vs 🇺🇸 100 Thieves | 1 - 0 | Bind"
+    );
+    assert_eq!(
+        format_match_text(&live[0], &flagged.tooltip),
+        "Valorant: 🇺🇸 Sentinels vs 🇺🇸 100 Thieves | 1 - 0 | Bind"
+    );
+    assert_eq!(
+        format_match_text(&live[1], &flagged.tray_item),
+        "Team Liquid vs Fnatic | 0 - 0 | Ascent"
+    );
+}
+
+#[test]
+fn custom_templates_are_kept_with_flags_on() {
+    let live = parse_live_matches(LIVE_SCORE).unwrap();
+    let (templates, _) = Templates::compile(&TemplateSettings {
+        flags: true,
+        cli: "{tag1} {score1}-{score2} {tag2}".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(format_match_text(&live[0], &templates.cli), "SEN 1-0 100T");
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::LIVE_SCORE;
+use valo_core::api::parse_live_matches;
+use valo_core::history::{HistoryDb, HistoryFilter};
+use valo_core::snapshot::{MatchEvent, MatchSnapshot};
+use valo_core::MatchSegment;
+
+fn snapshot(matches: Vec<MatchSegment>) -> MatchSnapshot {
+    MatchSnapshot {
+        updated_at: 1,
+        matches,
+        odds: Default::default(),
+        upcoming: Vec::new(),
+        offline_since: None,
+    }
+}
+
+fn live() -> Vec<MatchSegment> {
+    parse_live_matches(LIVE_SCORE).unwrap()
+}
+
+fn started(segment: &MatchSegment) -> MatchEvent {
+    MatchEvent::MatchStarted {
+        match_id: segment.match_id.clone(),
+        team1: segment.team1.clone(),
+        team2: segment.team2.clone(),
+    }
+}
+
+fn ended(segment: &MatchSegment) -> MatchEvent {
+    MatchEvent::MatchEnded {
+        match_id: segment.match_id.clone(),
+        last_seen: Box::new(segment.clone()),
+    }
+}
+
+fn filter(team: Option<&str>, event: Option<&str>) -> HistoryFilter {
+    HistoryFilter {
+        team: team.map(str::to_string),
+        event: event.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+// Every live match recorded once
+fn recorded() -> HistoryDb {
+    let db = HistoryDb::open_in_memory().unwrap();
+    let matches = live();
+    let snapshot = snapshot(matches.clone());
+    for segment in &matches {
+        db.record_event(&started(segment), &snapshot).unwrap();
+    }
+    db
+}
+
+#[test]
+fn events_record_matches_and_snapshots() {
+    let db = HistoryDb::open_in_memory().unwrap();
+    let mut matches = live();
+    db.record_event(&started(&matches[0]), &snapshot(matches.clone())).unwrap();
+
+    matches[0].team1_round_t = "5".to_string();
+    let rounds = MatchEvent::RoundsChanged {
+        match_id: matches[0].match_id.clone(),
+        rounds1: 9,
+        rounds2: 7,
+    };
+    db.record_event(&rounds, &snapshot(matches.clone())).unwrap();
+    // The same state again is not a new snapshot
+    db.record_event(&rounds, &snapshot(matches.clone())).unwrap();
+
+    let records = db
+        .query(&HistoryFilter {
+            include_snapshots: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.match_id, "449012");
+    assert_eq!((record.team1.as_str(), record.team2.as_str()), ("Sentinels", "100 Thieves"));
+    assert_eq!((record.score1, record.score2), (1, 0));
+    assert_eq!(record.ended_at, None);
+    let rounds: Vec<_> = record.snapshots.iter().map(|s| (s.rounds1, s.rounds2)).collect();
+    assert_eq!(rounds, [(7, 7), (9, 7)]);
+    assert_eq!(record.snapshots[1].map, "Bind");
+}
+
+#[test]
+fn ended_matches_get_a_winner() {
+    let db = recorded();
+    let matches = live();
+    let snapshot = snapshot(Vec::new());
+
+    let mut won = matches[0].clone();
+    won.score1 = "2".to_string();
+    db.record_event(&ended(&won), &snapshot).unwrap();
+    // Level when it left the feed: no winner
+    db.record_event(&ended(&matches[1]), &snapshot).unwrap();
+
+    let records = db.query(&HistoryFilter::default()).unwrap();
+    let sentinels = records.iter().find(|r| r.team1 == "Sentinels").unwrap();
+    assert!(sentinels.ended_at.is_some());
+    assert_eq!(sentinels.winner.as_deref(), Some("Sentinels"));
+    assert_eq!((sentinels.score1, sentinels.score2), (2, 0));
+    let liquid = records.iter().find(|r| r.team1 == "Team Liquid").unwrap();
+    assert!(liquid.ended_at.is_some());
+    assert_eq!(liquid.winner, None);
+}
+
+#[test]
+fn matches_seen_again_are_no_longer_ended() {
+    let db = recorded();
+    let matches = live();
+    db.record_event(&ended(&matches[0]), &snapshot(Vec::new())).unwrap();
+
+    db.record_event(&started(&matches[0]), &snapshot(matches.clone())).unwrap();
+
+    let records = db.query(&filter(Some("sentinels"), None)).unwrap();
+    assert_eq!(records[0].ended_at, None);
+    assert_eq!(records[0].winner, None);
+}
+
+#[test]
+fn queries_filter_by_team_and_event() {
+    let db = recorded();
+    let teams = |filter: HistoryFilter| -> Vec<String> {
+        db.query(&filter).unwrap().into_iter().map(|r| r.team1).collect()
+    };
+
+    assert_eq!(teams(filter(Some("THIEVES"), None)), ["Sentinels"]);
+    assert_eq!(teams(filter(None, Some("emea"))), ["Team Liquid"]);
+    assert_eq!(teams(filter(Some("liquid"), Some("americas"))), Vec::<String>::new());
+    assert_eq!(teams(filter(Some("  "), None)).len(), 2);
+    // LIKE wildcards in the input are plain characters
+    assert!(teams(filter(Some("%"), None)).is_empty());
+    assert!(teams(filter(Some("_"), None)).is_empty());
+    assert!(teams(filter(None, Some("vct_2025"))).is_empty());
+    assert_eq!(teams(filter(None, Some("vct 2025"))).len(), 2);
+}
+
+#[test]
+fn queries_filter_by_time_and_limit() {
+    let db = recorded();
+    let now = chrono::Utc::now().timestamp();
+    let query = |from: Option<i64>, to: Option<i64>, limit: Option<u32>| {
+        db.query(&HistoryFilter {
+            from,
+            to,
+            limit,
+            ..Default::default()
+        })
+        .unwrap()
+        .len()
+    };
+
+    assert_eq!(query(Some(now - 60), None, None), 2);
+    assert_eq!(query(Some(now + 60), None, None), 0);
+    assert_eq!(query(None, Some(now - 60), None), 0);
+    assert_eq!(query(None, None, Some(1)), 1);
+}
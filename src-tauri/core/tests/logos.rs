@@ -0,0 +1,139 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{temp_path, LIVE_SCORE};
+use std::path::PathBuf;
+use valo_core::api::parse_live_matches;
+use valo_core::logos::{
+    content_hash, is_allowed_logo_url, logo_src, parse_logo_request, placeholder_svg, with_logo_srcs, LogoCache,
+    LOGO_HOSTS,
+};
+
+const LOGO: &str = "PNG sentinels logo";
+
+// Path and query of a scheme URL, as the scheme handler gets it
+fn request_path(src: &str) -> &str {
+    let start = src.find("localhost").unwrap() + "localhost".len();
+    &src[start..]
+}
+
+fn image_files(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .filter(|entry| entry.as_ref().unwrap().file_name() != "index.json")
+        .count()
+}
+
+#[test]
+fn logo_urls_point_at_the_scheme() {
+    let src = logo_src("//owcdn.net/img/sen.png", "100 Thieves");
+    assert!(src.starts_with("logo://localhost/") || src.starts_with("http://logo.localhost/"));
+    assert_eq!(
+        parse_logo_request(request_path(&src)),
+        ("100 Thieves".to_string(), Some("https://owcdn.net/img/sen.png".to_string()))
+    );
+
+    let src = logo_src("", "KRÜ Esports");
+    assert_eq!(parse_logo_request(request_path(&src)), ("KRÜ Esports".to_string(), None));
+}
+
+#[test]
+fn match_logos_are_rewritten() {
+    let matches = with_logo_srcs(parse_live_matches(LIVE_SCORE).unwrap());
+    let (team, src) = parse_logo_request(request_path(&matches[0].team1_logo));
+    assert_eq!(team, "Sentinels");
+    assert_eq!(src.as_deref(), Some("https://owcdn.net/img/sentinels.png"));
+}
+
+#[test]
+fn placeholders_show_the_team_tag() {
+    let svg = placeholder_svg("Sentinels");
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(">SEN</text>"));
+    // Same color every time for a team
+    assert_eq!(svg, placeholder_svg("sentinels"));
+    assert!(placeholder_svg("").contains(">?</text>"));
+    assert!(placeholder_svg("<3 Esports").contains(">&lt;E</text>"));
+}
+
+#[tokio::test]
+async fn logos_are_downloaded_once_and_kept_on_disk() {
+    let server = MockServer::start().await;
+    server.respond("/img/sen.png", 200, LOGO);
+    let dir = temp_path("logos-cache");
+    let src = logo_src(&format!("{}/img/sen.png", server.url), "Sentinels");
+
+    let cache = LogoCache::load(Some(dir.clone())).with_hosts(&["127.0.0.1"]);
+    let asset = cache.serve(request_path(&src)).await;
+    assert_eq!(asset.content_type, "image/png");
+    assert_eq!(asset.bytes, LOGO.as_bytes());
+    cache.serve(request_path(&src)).await;
+    assert_eq!(server.requests().len(), 1);
+    assert!(dir.join(format!("{}.png", content_hash(LOGO.as_bytes()))).exists());
+
+    // After a restart
+    let cache = LogoCache::load(Some(dir.clone())).with_hosts(&["127.0.0.1"]);
+    assert_eq!(cache.serve(request_path(&src)).await.bytes, LOGO.as_bytes());
+    assert_eq!(server.requests().len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn identical_logos_are_stored_once() {
+    let server = MockServer::start().await;
+    server.respond("/img/sen.png", 200, LOGO);
+    server.respond("/img/sentinels.png", 200, LOGO);
+    let dir = temp_path("logos-dedupe");
+    let cache = LogoCache::load(Some(dir.clone()));
+
+    cache.fetch(&format!("{}/img/sen.png", server.url)).await.unwrap();
+    cache.fetch(&format!("{}/img/sentinels.png", server.url)).await.unwrap();
+
+    assert_eq!(image_files(&dir), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn failed_downloads_get_the_placeholder() {
+    let server = MockServer::start().await;
+    server.respond("/img/missing.png", 404, "not found");
+    server.respond("/img/page", 200, r#"{"error": "not an image"}"#);
+    let oversized = "x".repeat(3 * 1024 * 1024);
+    server.respond("/img/huge.png", 200, &oversized);
+    let dir = temp_path("logos-failed");
+    let cache = LogoCache::load(Some(dir.clone())).with_hosts(&["127.0.0.1"]);
+
+    for logo in ["/img/missing.png", "/img/page", "/img/huge.png"] {
+        let src = logo_src(&format!("{}{}", server.url, logo), "Sentinels");
+        let asset = cache.serve(request_path(&src)).await;
+        assert_eq!(asset.content_type, "image/svg+xml");
+        assert_eq!(asset.bytes, placeholder_svg("Sentinels").into_bytes());
+    }
+
+    // Not cached, so a later request tries again
+    assert!(!dir.exists() || image_files(&dir) == 0);
+}
+
+#[test]
+fn only_logo_hosts_are_allowed() {
+    let hosts: Vec<String> = LOGO_HOSTS.iter().map(|host| host.to_string()).collect();
+    assert!(is_allowed_logo_url("https://owcdn.net/img/sen.png", &hosts));
+    assert!(is_allowed_logo_url("https://www.vlr.gg/img/vlr/tmp/vlr.png", &hosts));
+    assert!(!is_allowed_logo_url("https://owcdn.net.example.com/a.png", &hosts));
+    assert!(!is_allowed_logo_url("https://evilvlr.gg/a.png", &hosts));
+    assert!(!is_allowed_logo_url("http://127.0.0.1:7878/api/snapshot", &hosts));
+    assert!(!is_allowed_logo_url("file:///etc/passwd", &hosts));
+}
+
+#[tokio::test]
+async fn other_hosts_get_the_placeholder() {
+    let server = MockServer::start().await;
+    server.respond("/img/sen.png", 200, LOGO);
+    let cache = LogoCache::load(None);
+
+    let src = logo_src(&format!("{}/img/sen.png", server.url), "Sentinels");
+    let asset = cache.serve(request_path(&src)).await;
+
+    assert_eq!(asset.content_type, "image/svg+xml");
+    assert!(server.requests().is_empty());
+}
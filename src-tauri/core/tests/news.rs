@@ -0,0 +1,116 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{temp_path, NEWS};
+use valo_core::api::parse_news;
+use valo_core::models::NewsArticle;
+use valo_core::news::{mentioned_teams, NewsFeed};
+use valo_core::source::{LiveSource, MatchSource};
+
+const NEWS_PATH: &str = "/v2/news";
+const NOW: i64 = 1743900000;
+
+fn article(title: &str, path: &str) -> NewsArticle {
+    NewsArticle {
+        title: title.to_string(),
+        description: String::new(),
+        date: String::new(),
+        author: String::new(),
+        url_path: path.to_string(),
+    }
+}
+
+fn teams(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[tokio::test]
+async fn news_is_typed() {
+    let server = MockServer::start().await;
+    server.respond(NEWS_PATH, 200, NEWS);
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+
+    let articles = source.news().await.unwrap();
+
+    assert_eq!(articles.len(), 3);
+    assert_eq!(articles[0].title, "Sentinels announce zekken contract extension");
+    assert_eq!(articles[1].author, "Ryan");
+    assert_eq!(articles[2].url_path, "/451101/americas-stage-1-week-3-preview");
+}
+
+#[test]
+fn teams_are_matched_as_whole_words() {
+    let articles = parse_news(NEWS).unwrap();
+    let followed = teams(&["sentinels", "G2", "Fnatic", "100 Thieves"]);
+
+    assert_eq!(mentioned_teams(&articles[0], &followed), ["sentinels"]);
+    // "G2A" is not G2
+    assert_eq!(mentioned_teams(&articles[1], &followed), ["Fnatic"]);
+    // Found in the description
+    assert_eq!(mentioned_teams(&articles[2], &followed), ["100 Thieves"]);
+}
+
+#[test]
+fn the_first_feed_only_fills_the_cache() {
+    let feed = NewsFeed::load(None);
+    let articles = parse_news(NEWS).unwrap();
+
+    assert!(feed.record(&articles, NOW).is_empty());
+    assert_eq!(feed.articles(&[], false).len(), 3);
+    assert!(feed.record(&articles, NOW + 600).is_empty());
+}
+
+#[test]
+fn new_articles_are_returned_once() {
+    let feed = NewsFeed::load(None);
+    feed.record(&parse_news(NEWS).unwrap()[1..], NOW);
+
+    let mut articles = parse_news(NEWS).unwrap();
+    articles.insert(0, article("LOUD reveal new roster", ""));
+    let added = feed.record(&articles, NOW + 600);
+    let titles: Vec<_> = added.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(titles, ["LOUD reveal new roster", "Sentinels announce zekken contract extension"]);
+
+    // Deduped by path, or by title when there is none
+    assert!(feed.record(&articles, NOW + 1200).is_empty());
+
+    let cached = feed.articles(&[], false);
+    assert_eq!(cached.len(), 4);
+    assert_eq!(cached[0].article.title, "LOUD reveal new roster");
+    assert_eq!(cached[0].seen_at, NOW + 600);
+    assert_eq!(cached[1].url, "https://www.vlr.gg/451120/sentinels-announce-zekken-contract-extension");
+}
+
+#[test]
+fn articles_are_filtered_by_followed_teams() {
+    let feed = NewsFeed::load(None);
+    feed.record(&parse_news(NEWS).unwrap(), NOW);
+
+    let followed = teams(&["Sentinels", "MIBR"]);
+    let items = feed.articles(&followed, true);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].teams, ["Sentinels"]);
+    assert_eq!(items[1].teams, ["MIBR"]);
+
+    // Every article, still tagged with the teams it mentions
+    let items = feed.articles(&followed, false);
+    assert_eq!(items.len(), 3);
+    assert!(items[1].teams.is_empty());
+}
+
+#[tokio::test]
+async fn news_survives_a_restart_and_a_failed_fetch() {
+    let path = temp_path("news.json");
+    let server = MockServer::start().await;
+    server.respond_sequence(NEWS_PATH, &[(200, NEWS), (500, "<html>error</html>")]);
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+
+    NewsFeed::load(Some(path.clone())).refresh(&source, NOW).await.unwrap();
+    let feed = NewsFeed::load(Some(path.clone()));
+    assert!(feed.refresh(&source, NOW + 600).await.is_err());
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(feed.articles(&[], false).len(), 3);
+    // Seen before the restart, so not new
+    assert!(feed.record(&parse_news(NEWS).unwrap(), NOW + 1200).is_empty());
+}
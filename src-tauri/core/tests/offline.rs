@@ -0,0 +1,167 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::LIVE_SCORE;
+use std::convert::identity;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use valo_core::api::parse_live_matches;
+use valo_core::format::tray_tooltip;
+use valo_core::poller::{run_poller, wait_until_online};
+use valo_core::snapshot::{MatchEvent, MatchStore};
+use valo_core::source::{is_offline_error, LiveSource, MatchSource, SourceError};
+use valo_core::template::Templates;
+
+// A local address nothing listens on
+async fn closed_address() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+    address.to_string()
+}
+
+fn source_at(address: &str) -> LiveSource {
+    let url = format!("http://{}", address);
+    LiveSource::with_base_urls(&url, &url, None)
+}
+
+#[tokio::test]
+async fn failed_connections_are_offline() {
+    let source = source_at(&closed_address().await);
+
+    let error = source.live_matches().await.unwrap_err();
+    assert!(is_offline_error(&error));
+    assert!(!source.reachable().await);
+}
+
+#[tokio::test]
+async fn failed_dns_lookups_are_offline() {
+    let source = LiveSource::with_base_urls("http://vlrggapi.invalid", "http://vlrggapi.invalid", None);
+
+    let error = source.live_matches().await.unwrap_err();
+    assert!(is_offline_error(&error));
+    assert!(!source.reachable().await);
+}
+
+#[tokio::test]
+async fn stalled_requests_are_offline() {
+    // Accepts connections but never answers, like a half-open network
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let _stall = tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            connections.push(socket);
+        }
+    });
+    let client = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+
+    let error: SourceError = client.get(&url).send().await.unwrap_err().into();
+    assert!(is_offline_error(&error));
+}
+
+#[tokio::test]
+async fn api_failures_are_not_offline() {
+    let server = MockServer::start().await;
+    server.respond("/v2/match", 500, "<html>Internal Server Error</html>");
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+
+    let error = source.live_matches().await.unwrap_err();
+    assert!(!is_offline_error(&error));
+    assert!(source.reachable().await);
+}
+
+#[test]
+fn tooltip_shows_the_offline_state() {
+    let store = MatchStore::new();
+    store.update(parse_live_matches(LIVE_SCORE).unwrap(), Default::default());
+    let template = &Templates::default().tooltip;
+
+    store.set_offline(Some(1743900000));
+    assert_eq!(tray_tooltip(&store.snapshot(), template), "Valorant: Offline, waiting for a connection");
+    // The last matches are kept
+    assert_eq!(store.snapshot().matches.len(), 2);
+
+    store.set_offline(None);
+    assert_eq!(
+        tray_tooltip(&store.snapshot(), template),
+        "Valorant: Sentinels vs 100 Thieves | 1 - 0 | Bind"
+    );
+}
+
+#[tokio::test]
+async fn the_poller_reports_going_offline() {
+    let source = source_at(&closed_address().await);
+    let store = MatchStore::new();
+    let mut updates = Vec::new();
+
+    let poller = run_poller(&store, &source, Duration::from_millis(50), identity, |snapshot| {
+        updates.push(snapshot.offline_since)
+    });
+    let _ = tokio::time::timeout(Duration::from_millis(500), poller).await;
+
+    // Reported once, then paused on the probe rather than polling every 50ms
+    assert_eq!(updates.len(), 1);
+    assert!(updates[0].is_some());
+}
+
+#[tokio::test]
+async fn probing_stops_when_the_host_is_back() {
+    let address = closed_address().await;
+    let source = source_at(&address);
+
+    // The probe only needs an answer, not a successful one
+    let reconnect = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        MockServer::start_at(&address).await
+    });
+    let waited = tokio::time::timeout(Duration::from_secs(5), wait_until_online(&source, Duration::from_millis(50))).await;
+
+    assert!(waited.is_ok());
+    drop(reconnect.await.unwrap());
+}
+
+#[tokio::test]
+async fn the_poller_reports_coming_back_online() {
+    let address = closed_address().await;
+    let source = source_at(&address);
+    let store = MatchStore::new();
+    let mut events = store.subscribe();
+    let mut updates = Vec::new();
+
+    let reconnect = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let server = MockServer::start_at(&address).await;
+        server.respond("/v2/match", 200, LIVE_SCORE);
+        server
+    });
+    let poller = run_poller(&store, &source, Duration::from_millis(50), identity, |snapshot| {
+        updates.push(snapshot.offline_since)
+    });
+    let mut seen = Vec::new();
+    let back_online = async {
+        while let Ok(event) = events.recv().await {
+            seen.push(event.clone());
+            if event == MatchEvent::Online {
+                break;
+            }
+        }
+    };
+    // The probe runs every few seconds, so coming back takes one probe interval
+    let finished = tokio::time::timeout(Duration::from_secs(10), async {
+        tokio::select! {
+            _ = poller => {}
+            _ = back_online => {}
+        }
+    })
+    .await;
+
+    assert!(finished.is_ok());
+    assert_eq!(updates.len(), 2);
+    assert!(updates[0].is_some());
+    assert_eq!(updates[1], None);
+    assert!(matches!(seen[0], MatchEvent::Offline { .. }));
+    assert_eq!(seen.last(), Some(&MatchEvent::Online));
+    assert_eq!(store.snapshot().matches.len(), 2);
+    drop(reconnect.await.unwrap());
+}
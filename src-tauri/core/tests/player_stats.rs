@@ -0,0 +1,92 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{LIVE_SCORE, MATCH_PAGE};
+use valo_core::api::{match_page_path, parse_live_matches};
+use valo_core::player_stats::{current_map_scoreboard, parse_scoreboard};
+use valo_core::source::LiveSource;
+
+const PAGE_PATH: &str = "/449012/sentinels-vs-100-thieves-vct-2025-americas-stage-1-w3";
+
+#[test]
+fn match_pages_resolve_to_paths() {
+    assert_eq!(
+        match_page_path("https://www.vlr.gg/449012/sentinels-vs-100-thieves").as_deref(),
+        Some("/449012/sentinels-vs-100-thieves")
+    );
+    assert_eq!(match_page_path("/449010/sentinels-vs-100-thieves/").as_deref(), Some("/449010/sentinels-vs-100-thieves"));
+    assert_eq!(match_page_path("https://www.vlr.gg"), None);
+    assert_eq!(match_page_path(""), None);
+}
+
+#[test]
+fn current_map_is_picked_by_name() {
+    let scoreboard = parse_scoreboard(MATCH_PAGE, "449012", "bind", 0).unwrap();
+
+    assert_eq!(scoreboard.map, "Bind");
+    assert_eq!(scoreboard.map_number, 2);
+    assert_eq!(scoreboard.team1.team, "Sentinels");
+    assert_eq!(scoreboard.team2.team, "100 Thieves");
+    assert_eq!((scoreboard.team1.score, scoreboard.team2.score), (Some(7), Some(7)));
+    assert_eq!(scoreboard.team1.players.len(), 5);
+    assert_eq!(scoreboard.team2.players.len(), 5);
+
+    let tenz = &scoreboard.team1.players[0];
+    assert_eq!(tenz.name, "TenZ");
+    assert_eq!(tenz.team, "SEN");
+    assert_eq!(tenz.agents, ["Jett"]);
+    assert_eq!(tenz.rating, Some(1.31));
+    assert_eq!(tenz.acs, Some(264));
+    assert_eq!((tenz.kills, tenz.deaths, tenz.assists), (Some(11), Some(7), Some(3)));
+    assert_eq!(tenz.kast, Some(75));
+    assert_eq!(tenz.adr, Some(171));
+    assert_eq!(tenz.headshot_percent, Some(28));
+    assert_eq!((tenz.first_kills, tenz.first_deaths), (Some(3), Some(1)));
+}
+
+#[test]
+fn map_number_is_the_fallback() {
+    let scoreboard = parse_scoreboard(MATCH_PAGE, "449012", "", 1).unwrap();
+    assert_eq!(scoreboard.map, "Haven");
+    assert_eq!(scoreboard.team1.score, Some(13));
+
+    assert!(parse_scoreboard(MATCH_PAGE, "449012", "Lotus", 3).is_none());
+    assert!(parse_scoreboard("<html><body>Match not found</body></html>", "1", "Bind", 1).is_none());
+}
+
+#[test]
+fn blank_stats_are_missing() {
+    let page = MATCH_PAGE.replace(
+        "<span class=\"side mod-side mod-both\">171</span>",
+        "<span class=\"side mod-side mod-both\">&nbsp;</span>",
+    );
+    let scoreboard = parse_scoreboard(&page, "449012", "Bind", 2).unwrap();
+    assert_eq!(scoreboard.team1.players[0].adr, None);
+    assert_eq!(scoreboard.team1.players[0].acs, Some(264));
+}
+
+#[tokio::test]
+async fn live_matches_fetch_their_match_page() {
+    let server = MockServer::start().await;
+    server.respond(PAGE_PATH, 200, MATCH_PAGE);
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None).with_vlr_url(&server.url);
+    let matches = parse_live_matches(LIVE_SCORE).unwrap();
+
+    let scoreboard = current_map_scoreboard(&source, &matches[0]).await.unwrap();
+    assert_eq!(scoreboard.match_id, "449012");
+    assert_eq!(scoreboard.map, "Bind");
+    assert_eq!(server.requests()[0].path, PAGE_PATH);
+
+    // Team Liquid vs Fnatic has no match page
+    assert!(current_map_scoreboard(&source, &matches[1]).await.is_err());
+}
+
+#[tokio::test]
+async fn missing_match_pages_are_errors() {
+    let server = MockServer::start().await;
+    server.respond(PAGE_PATH, 404, "<html>Page not found</html>");
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None).with_vlr_url(&server.url);
+    let matches = parse_live_matches(LIVE_SCORE).unwrap();
+
+    assert!(current_map_scoreboard(&source, &matches[0]).await.is_err());
+}
@@ -0,0 +1,146 @@
+use valo_core::probability::{
+    best_of, estimate, estimate_with_prior, implied_chance, map_win_chance, series_win_chance,
+    starting_side, strength_for_map_chance, Side,
+};
+use valo_core::{MatchSegment, PolymarketOdds};
+
+fn close(actual: f64, expected: f64) -> bool {
+    (actual - expected).abs() < 1e-6
+}
+
+// A live match on `rounds` = (team1 CT, team1 T, team2 CT, team2 T) with series score `score`
+fn live(score: (u32, u32), rounds: (u32, u32, u32, u32), series: &str) -> MatchSegment {
+    let json = serde_json::json!({
+        "team1": "Sentinels",
+        "team2": "100 Thieves",
+        "score1": score.0.to_string(),
+        "score2": score.1.to_string(),
+        "team1_round_ct": rounds.0.to_string(),
+        "team1_round_t": rounds.1.to_string(),
+        "team2_round_ct": rounds.2.to_string(),
+        "team2_round_t": rounds.3.to_string(),
+        "time_until_match": "LIVE",
+        "match_series": series,
+    });
+    serde_json::from_value(json).unwrap()
+}
+
+fn odds(team1: Option<f64>, team2: Option<f64>) -> PolymarketOdds {
+    PolymarketOdds {
+        team1_odds: team1,
+        team2_odds: team2,
+        market_url: None,
+    }
+}
+
+#[test]
+fn even_teams_at_the_start_are_a_coin_flip() {
+    let estimate = estimate(&live((0, 0), (0, 0, 0, 0), "Regular Season"), None);
+    assert!(close(estimate.map1, 0.5));
+    assert!(close(estimate.series1, 0.5));
+    assert_eq!(estimate.best_of, 3);
+    assert_eq!(estimate.market1, None);
+    assert_eq!(estimate.edge1, None);
+}
+
+#[test]
+fn map_point_and_decided_maps() {
+    assert!(close(map_win_chance(13, 5, 0.5, Side::Defense), 1.0));
+    assert!(close(map_win_chance(11, 13, 0.5, Side::Defense), 0.0));
+    // 12-0 is only lost by dropping the next 13 rounds
+    assert!(map_win_chance(12, 0, 0.5, Side::Defense) > 0.999);
+    assert!(map_win_chance(0, 12, 0.5, Side::Defense) < 0.001);
+}
+
+#[test]
+fn overtime_from_a_tie_is_even_for_even_teams() {
+    // One round on each side per overtime pair, so the side advantage cancels out
+    assert!(close(map_win_chance(12, 12, 0.5, Side::Defense), 0.5));
+    assert!(close(map_win_chance(14, 14, 0.5, Side::Attack), 0.5));
+    // 13-12: win the next round (on attack, 48%), or lose it and go back to an even tie
+    assert!(close(map_win_chance(13, 12, 0.5, Side::Defense), 0.48 + 0.52 * 0.5));
+}
+
+#[test]
+fn defending_side_is_favoured_in_the_current_half() {
+    // 6-6 at half time: team 1 defends the second half after starting on attack
+    let after_attack_half = map_win_chance(6, 6, 0.5, Side::Attack);
+    let after_defense_half = map_win_chance(6, 6, 0.5, Side::Defense);
+    assert!(after_attack_half > 0.5);
+    assert!(after_defense_half < 0.5);
+    assert!(close(after_attack_half + after_defense_half, 1.0));
+}
+
+#[test]
+fn starting_side_comes_from_the_half_splits() {
+    // First half: team 1 won 5 on CT, team 2 won 3 on T
+    assert_eq!(starting_side(&live((0, 0), (5, 0, 0, 3), "")), Some(Side::Defense));
+    assert_eq!(starting_side(&live((0, 0), (0, 4, 2, 0), "")), Some(Side::Attack));
+    // Second half: 12 rounds were played with team 1 on attack
+    assert_eq!(starting_side(&live((0, 0), (3, 7, 5, 2), "")), Some(Side::Attack));
+    assert_eq!(starting_side(&live((0, 0), (0, 0, 0, 0), "")), None);
+}
+
+#[test]
+fn series_chances() {
+    assert!(close(series_win_chance(2, 2, 0.5), 0.5));
+    assert!(close(series_win_chance(2, 2, 0.6), 0.6 * 0.6 + 2.0 * 0.6 * 0.6 * 0.4));
+    assert!(close(series_win_chance(0, 1, 0.1), 1.0));
+    assert!(close(series_win_chance(3, 0, 0.9), 0.0));
+}
+
+#[test]
+fn map_up_in_a_best_of_three() {
+    // 1-0 up with the second map about to start
+    let estimate = estimate(&live((1, 0), (0, 0, 0, 0), "Playoffs"), None);
+    assert!(close(estimate.series1, 0.75));
+}
+
+#[test]
+fn leading_on_the_map_raises_both_chances() {
+    let level = estimate(&live((0, 0), (3, 2, 3, 2), ""), None);
+    let ahead = estimate(&live((0, 0), (6, 4, 2, 0), ""), None);
+    assert!(ahead.map1 > level.map1);
+    assert!(ahead.map1 > 0.85);
+    assert!(ahead.series1 > 0.5 && ahead.series1 < ahead.map1);
+}
+
+#[test]
+fn estimates_are_deterministic() {
+    let segment = live((1, 1), (4, 3, 2, 5), "Upper Final");
+    assert_eq!(estimate(&segment, None), estimate(&segment, None));
+}
+
+#[test]
+fn priors_shift_the_estimate() {
+    assert!(close(strength_for_map_chance(0.5), 0.5));
+    let strength = strength_for_map_chance(0.7);
+    assert!(strength > 0.5 && strength < 0.6);
+    let fresh_map = (map_win_chance(0, 0, strength, Side::Defense)
+        + map_win_chance(0, 0, strength, Side::Attack))
+        / 2.0;
+    assert!(close(fresh_map, 0.7));
+
+    let segment = live((0, 0), (0, 0, 0, 0), "");
+    assert!(close(estimate_with_prior(&segment, None, 0.7).map1, 0.7));
+}
+
+#[test]
+fn edge_against_the_market() {
+    // Prices add up to 1.05; the overround is removed before comparing
+    let market = odds(Some(0.6), Some(0.45));
+    assert!(close(implied_chance(&market).unwrap(), 0.6 / 1.05));
+    assert!(close(implied_chance(&odds(None, Some(0.3))).unwrap(), 0.7));
+    assert_eq!(implied_chance(&odds(None, None)), None);
+
+    let estimate = estimate(&live((0, 0), (0, 0, 0, 0), ""), Some(&market));
+    assert!(close(estimate.edge1.unwrap(), 0.5 - 0.6 / 1.05));
+}
+
+#[test]
+fn series_length() {
+    assert_eq!(best_of(&live((0, 0), (0, 0, 0, 0), "Playoffs: Grand Final")), 5);
+    assert_eq!(best_of(&live((2, 1), (0, 0, 0, 0), "Playoffs")), 5);
+    assert_eq!(best_of(&live((0, 0), (0, 0, 0, 0), "Group Stage: Bo1")), 1);
+    assert_eq!(best_of(&live((1, 0), (0, 0, 0, 0), "Regular Season")), 3);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{temp_path, LIVE_SCORE, RANKINGS_EU};
+use valo_core::api::{parse_live_matches, parse_rankings};
+use valo_core::poller::poll_once;
+use valo_core::models::TeamRank;
+use valo_core::rankings::{region_name, RankingCache, RANKINGS_MAX_AGE_SECS};
+use valo_core::snapshot::MatchStore;
+use valo_core::source::{LiveSource, MatchSource};
+
+const RANKINGS_PATH: &str = "/v2/rankings";
+const LIVE_PATH: &str = "/v2/match?q=live_score";
+const NOW: i64 = 1743900000;
+
+async fn live_source() -> (MockServer, LiveSource) {
+    let server = MockServer::start().await;
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+    (server, source)
+}
+
+#[tokio::test]
+async fn rankings_are_typed() {
+    let (server, source) = live_source().await;
+    server.respond(RANKINGS_PATH, 200, RANKINGS_EU);
+
+    let teams = source.rankings("eu").await.unwrap();
+
+    assert_eq!(teams.len(), 3);
+    assert_eq!(teams[0].rank, 1);
+    assert_eq!(teams[0].team, "Fnatic");
+    assert_eq!(teams[0].record, "14-4");
+    assert_eq!(server.requests()[0].query_param("region").as_deref(), Some("eu"));
+}
+
+#[test]
+fn numeric_ranks_are_accepted() {
+    let teams = parse_rankings(&RANKINGS_EU.replace("\"rank\": \"2\"", "\"rank\": 2")).unwrap();
+    assert_eq!(teams[1].rank, 2);
+    assert!(parse_rankings(&RANKINGS_EU.replace("\"rank\": \"2\"", "\"rank\": \"n/a\"")).is_err());
+}
+
+#[tokio::test]
+async fn rankings_are_cached_for_a_day() {
+    let (server, source) = live_source().await;
+    server.respond(RANKINGS_PATH, 200, RANKINGS_EU);
+    let cache = RankingCache::load(None);
+
+    cache.get(&source, "eu", NOW).await.unwrap();
+    cache.get(&source, "EU", NOW + 3600).await.unwrap();
+    assert_eq!(server.requests().len(), 1);
+
+    cache.get(&source, "eu", NOW + RANKINGS_MAX_AGE_SECS).await.unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn stale_rankings_are_kept_when_a_refresh_fails() {
+    let (server, source) = live_source().await;
+    server.respond_sequence(RANKINGS_PATH, &[(200, RANKINGS_EU), (500, "<html>error</html>")]);
+    let cache = RankingCache::load(None);
+
+    cache.get(&source, "eu", NOW).await.unwrap();
+    let teams = cache.get(&source, "eu", NOW + 2 * RANKINGS_MAX_AGE_SECS).await.unwrap();
+    assert_eq!(teams.len(), 3);
+    assert_eq!(cache.cached("eu").unwrap().fetched_at, NOW);
+
+    // Nothing cached to fall back on
+    assert!(cache.get(&source, "na", NOW).await.is_err());
+}
+
+#[tokio::test]
+async fn unknown_regions_are_rejected() {
+    let (server, source) = live_source().await;
+    let cache = RankingCache::load(None);
+
+    assert!(cache.get(&source, "mars", NOW).await.is_err());
+    assert!(server.requests().is_empty());
+    assert_eq!(region_name("eu"), Some("EMEA"));
+    assert_eq!(region_name("mars"), None);
+}
+
+#[test]
+fn matches_get_the_rank_of_each_team() {
+    let cache = RankingCache::load(None);
+    cache.insert("eu", parse_rankings(RANKINGS_EU).unwrap(), NOW);
+
+    let matches = cache.annotate(parse_live_matches(LIVE_SCORE).unwrap());
+
+    // Sentinels vs 100 Thieves: neither is in the EMEA ranking
+    assert_eq!(matches[0].rank1, None);
+    let rank = |rank| {
+        Some(TeamRank {
+            rank,
+            region: "eu".to_string(),
+            region_name: "EMEA".to_string(),
+        })
+    };
+    assert_eq!(matches[1].rank1, rank(3));
+    assert_eq!(matches[1].rank2, rank(1));
+}
+
+#[test]
+fn rankings_survive_a_restart() {
+    let path = temp_path("rankings.json");
+    RankingCache::load(Some(path.clone())).insert("eu", parse_rankings(RANKINGS_EU).unwrap(), NOW);
+
+    let cache = RankingCache::load(Some(path.clone()));
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(cache.cached("eu").unwrap().fetched_at, NOW);
+    assert_eq!(cache.rank("Fnatic").map(|r| r.rank), Some(1));
+}
+
+#[tokio::test]
+async fn polled_matches_carry_ranks() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE);
+    let cache = RankingCache::load(None);
+    cache.insert("eu", parse_rankings(RANKINGS_EU).unwrap(), NOW);
+    let store = MatchStore::new();
+
+    poll_once(&store, &source, |matches| cache.annotate(matches)).await.unwrap();
+
+    let snapshot = store.snapshot();
+    assert_eq!(snapshot.matches[1].rank2.as_ref().map(|r| r.rank), Some(1));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{temp_path, RESULTS};
+use valo_core::api::parse_results;
+use valo_core::probability::estimate_with_prior;
+use valo_core::ratings::{expected_score, RatingBook, INITIAL_RATING};
+use valo_core::source::{LiveSource, MatchSource};
+use valo_core::MatchSegment;
+
+const RESULTS_PATH: &str = "/v2/match?q=results";
+
+fn close(actual: f64, expected: f64) -> bool {
+    (actual - expected).abs() < 1e-6
+}
+
+#[tokio::test]
+async fn results_are_fetched_with_ids() {
+    let server = MockServer::start().await;
+    server.respond(RESULTS_PATH, 200, RESULTS);
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+
+    let results = source.results().await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].team1, "Sentinels");
+    assert_eq!(results[0].score1, "2");
+    assert_eq!(results[0].match_id, "449010");
+    assert_eq!(server.requests()[0].query_param("q").as_deref(), Some("results"));
+}
+
+#[test]
+fn equal_ratings_expect_an_even_map() {
+    assert!(close(expected_score(1500.0, 1500.0), 0.5));
+    // 400 points is ten to one
+    assert!(close(expected_score(1900.0, 1500.0), 10.0 / 11.0));
+}
+
+#[test]
+fn winners_gain_what_losers_lose() {
+    let book = RatingBook::load(None);
+    assert_eq!(book.record(&parse_results(RESULTS).unwrap(), 100), 3);
+
+    let ratings = book.ratings();
+    let teams: Vec<_> = ratings.iter().map(|t| t.team.as_str()).collect();
+    assert_eq!(teams, ["Sentinels", "NRG", "100 Thieves", "MIBR"]);
+    let total: f64 = ratings.iter().map(|t| t.rating).sum();
+    assert!(close(total, 4.0 * INITIAL_RATING));
+
+    let sentinels = book.rating("sentinels").unwrap();
+    assert_eq!((sentinels.maps_won, sentinels.maps_lost, sentinels.matches), (4, 1, 2));
+    assert_eq!(sentinels.updated_at, 100);
+}
+
+#[test]
+fn results_are_applied_once() {
+    let book = RatingBook::load(None);
+    let results = parse_results(RESULTS).unwrap();
+    book.record(&results, 100);
+    let before = book.ratings();
+
+    assert_eq!(book.record(&results, 200), 0);
+    assert_eq!(book.ratings(), before);
+}
+
+#[test]
+fn unplayed_series_are_skipped() {
+    let book = RatingBook::load(None);
+    // Sentinels vs 100 Thieves listed as 0-0
+    let forfeit = RESULTS.replace(
+        "\"score1\": \"2\",\n        \"score2\": \"1\"",
+        "\"score1\": \"0\",\n        \"score2\": \"0\"",
+    );
+    let forfeit = parse_results(&forfeit).unwrap();
+    assert_eq!(book.record(&forfeit, 100), 2);
+    assert!(book.rating("100 Thieves").is_none());
+}
+
+#[test]
+fn predictions_favour_the_higher_rated_team() {
+    let book = RatingBook::load(None);
+    book.record(&parse_results(RESULTS).unwrap(), 100);
+
+    let prediction = book.predict("Sentinels", "MIBR", 3);
+    assert!(prediction.rated);
+    assert!(prediction.map1 > 0.5);
+    // A longer series favours the stronger team more than a single map
+    assert!(prediction.series1 > prediction.map1);
+    assert!(close(book.predict("MIBR", "Sentinels", 3).series1, 1.0 - prediction.series1));
+
+    let unknown = book.predict("Sentinels", "Paper Rex", 3);
+    assert!(!unknown.rated);
+    assert!(close(unknown.rating2, INITIAL_RATING));
+    assert!(unknown.map1 > 0.5);
+}
+
+#[test]
+fn ratings_give_a_prior_for_matches_without_a_market() {
+    let book = RatingBook::load(None);
+    book.record(&parse_results(RESULTS).unwrap(), 100);
+    let upcoming: MatchSegment = serde_json::from_value(serde_json::json!({
+        "team1": "Sentinels",
+        "team2": "MIBR",
+        "match_series": "Regular Season",
+    }))
+    .unwrap();
+
+    let estimate = estimate_with_prior(&upcoming, None, book.map_chance("Sentinels", "MIBR"));
+    let prediction = book.predict("Sentinels", "MIBR", 3);
+    assert!((estimate.map1 - prediction.map1).abs() < 1e-3);
+    assert!((estimate.series1 - prediction.series1).abs() < 1e-3);
+    assert_eq!(estimate.market1, None);
+}
+
+#[test]
+fn ratings_survive_a_restart() {
+    let path = temp_path("ratings-restart.json");
+    let book = RatingBook::load(Some(path.clone()));
+    let results = parse_results(RESULTS).unwrap();
+    book.record(&results, 100);
+    let before = book.ratings();
+    drop(book);
+
+    let book = RatingBook::load(Some(path.clone()));
+    let _ = std::fs::remove_file(&path);
+    let after = book.ratings();
+    assert_eq!(after.len(), before.len());
+    for (after, before) in after.iter().zip(&before) {
+        assert_eq!(after.team, before.team);
+        assert!(close(after.rating, before.rating));
+    }
+    assert_eq!(book.record(&results, 200), 0);
+}
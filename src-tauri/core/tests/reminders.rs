@@ -0,0 +1,153 @@
+mod common;
+
+use common::{temp_path, UPCOMING};
+use valo_core::api::parse_live_matches;
+use valo_core::reminders::ReminderScheduler;
+use valo_core::settings::{FollowSettings, ReminderSettings};
+use valo_core::MatchSegment;
+
+// MIBR vs NRG starts at 2025-04-05 01:00 UTC in the fixture
+const MIBR_START: i64 = 1743814800;
+
+fn upcoming(start: &str) -> Vec<MatchSegment> {
+    parse_live_matches(&UPCOMING.replace("2025-04-05 01:00:00", start)).unwrap()
+}
+
+fn follow_mibr() -> FollowSettings {
+    FollowSettings {
+        teams: vec!["MIBR".to_string()],
+        events: Vec::new(),
+    }
+}
+
+fn settings() -> ReminderSettings {
+    ReminderSettings {
+        enabled: true,
+        minutes_before: 15,
+    }
+}
+
+#[test]
+fn fires_once_before_followed_matches() {
+    let mut scheduler = ReminderScheduler::load(None);
+    let now = MIBR_START - 3600;
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), now);
+    assert_eq!(scheduler.reminders.len(), 1);
+
+    assert!(scheduler.take_due(MIBR_START - 16 * 60).is_empty());
+    let due = scheduler.take_due(MIBR_START - 15 * 60);
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].title(), "MIBR vs NRG");
+    assert_eq!(
+        due[0].body(MIBR_START - 15 * 60),
+        "Starts in 15 minutes · VCT 2025: Americas Stage 1"
+    );
+    assert!(scheduler.take_due(MIBR_START - 60).is_empty());
+}
+
+#[test]
+fn survives_a_restart() {
+    let path = temp_path("reminders-restart.json");
+    let mut scheduler = ReminderScheduler::load(Some(path.clone()));
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), MIBR_START - 3600);
+    drop(scheduler);
+
+    // The app was closed when the reminder came due but the match hasn't started
+    let mut scheduler = ReminderScheduler::load(Some(path.clone()));
+    assert_eq!(scheduler.take_due(MIBR_START - 5 * 60).len(), 1);
+
+    // Fired reminders stay fired after another restart
+    let mut scheduler = ReminderScheduler::load(Some(path.clone()));
+    let _ = std::fs::remove_file(&path);
+    assert!(scheduler.take_due(MIBR_START - 60).is_empty());
+}
+
+#[test]
+fn missed_reminders_for_started_matches_are_skipped() {
+    let mut scheduler = ReminderScheduler::load(None);
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), MIBR_START - 3600);
+    assert!(scheduler.take_due(MIBR_START + 60).is_empty());
+    assert!(scheduler.reminders[0].fired);
+}
+
+#[test]
+fn delayed_starts_are_rescheduled() {
+    let mut scheduler = ReminderScheduler::load(None);
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), MIBR_START - 3600);
+    assert_eq!(scheduler.take_due(MIBR_START - 15 * 60).len(), 1);
+
+    // The API moves the match back an hour after the first reminder
+    let now = MIBR_START - 10 * 60;
+    scheduler.sync(&upcoming("2025-04-05 02:00:00"), &follow_mibr(), &settings(), now);
+    assert!(scheduler.take_due(now).is_empty());
+
+    let due = scheduler.take_due(MIBR_START + 45 * 60);
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].starts_at, MIBR_START + 3600);
+    assert!(due[0].body(MIBR_START + 45 * 60).starts_with("Delayed, starts in 15 minutes"));
+}
+
+#[test]
+fn earlier_starts_move_pending_reminders() {
+    let mut scheduler = ReminderScheduler::load(None);
+    let now = MIBR_START - 3 * 3600;
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), now);
+    scheduler.sync(&upcoming("2025-04-05 00:00:00"), &follow_mibr(), &settings(), now);
+
+    assert_eq!(scheduler.take_due(MIBR_START - 3600 - 15 * 60).len(), 1);
+}
+
+#[test]
+fn pending_reminders_follow_a_new_lead_time() {
+    let mut scheduler = ReminderScheduler::load(None);
+    let now = MIBR_START - 3600;
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), now);
+
+    let earlier = ReminderSettings {
+        minutes_before: 30,
+        ..settings()
+    };
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &earlier, now);
+
+    assert_eq!(scheduler.take_due(MIBR_START - 30 * 60).len(), 1);
+}
+
+#[test]
+fn nothing_is_scheduled_without_follows() {
+    let mut scheduler = ReminderScheduler::load(None);
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &FollowSettings::default(), &settings(), MIBR_START - 3600);
+
+    assert!(scheduler.reminders.is_empty());
+}
+
+#[test]
+fn unfollowing_drops_pending_reminders() {
+    let mut scheduler = ReminderScheduler::load(None);
+    let now = MIBR_START - 3600;
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), now);
+
+    let others = FollowSettings {
+        teams: vec!["Fnatic".to_string()],
+        events: Vec::new(),
+    };
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &others, &settings(), now);
+
+    assert!(scheduler.reminders.is_empty());
+    assert!(scheduler.take_due(MIBR_START - 15 * 60).is_empty());
+}
+
+#[test]
+fn turning_reminders_off_drops_pending_ones() {
+    let mut scheduler = ReminderScheduler::load(None);
+    let now = MIBR_START - 3600;
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &settings(), now);
+
+    let off = ReminderSettings {
+        enabled: false,
+        ..settings()
+    };
+    scheduler.sync(&upcoming("2025-04-05 01:00:00"), &follow_mibr(), &off, now);
+
+    assert!(scheduler.reminders.is_empty());
+    assert!(scheduler.take_due(MIBR_START - 15 * 60).is_empty());
+}
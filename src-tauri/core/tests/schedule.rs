@@ -0,0 +1,96 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use valo_core::api::parse_live_matches;
+use valo_core::schedule::{
+    countdown, format_start_time, market_date, parse_start, with_start_times, DisplayZone,
+};
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+fn zone(name: &str) -> DisplayZone {
+    DisplayZone::parse(Some(name)).unwrap()
+}
+
+#[test]
+fn parses_api_timestamps() {
+    let start = utc(2025, 4, 24, 22, 0);
+    assert_eq!(parse_start("1745532000"), Some(start));
+    assert_eq!(parse_start("2025-04-24 22:00:00"), Some(start));
+    assert_eq!(parse_start("2025-04-25T00:00:00+02:00"), Some(start));
+    assert_eq!(parse_start(""), None);
+    assert_eq!(parse_start("soon"), None);
+}
+
+#[test]
+fn rejects_unknown_timezones() {
+    assert_eq!(DisplayZone::parse(None), Ok(DisplayZone::Local));
+    assert_eq!(DisplayZone::parse(Some("local")), Ok(DisplayZone::Local));
+    assert!(DisplayZone::parse(Some("Mars/Olympus")).is_err());
+}
+
+#[test]
+fn start_after_midnight_in_display_zone_is_tomorrow() {
+    // 22:30 UTC is already the next day in Berlin
+    let now = utc(2025, 4, 24, 12, 0);
+    let start = utc(2025, 4, 24, 22, 30);
+    assert_eq!(format_start_time(start, zone("UTC"), now), "Today 22:30 UTC");
+    assert_eq!(
+        format_start_time(start, zone("Europe/Berlin"), now),
+        "Tomorrow 00:30 CEST"
+    );
+}
+
+#[test]
+fn start_time_uses_the_offset_in_effect_at_the_start() {
+    // Now is before the US DST switch, the match is after it
+    let now = utc(2025, 3, 8, 12, 0);
+    let start = utc(2025, 3, 11, 0, 0);
+    assert_eq!(
+        format_start_time(start, zone("America/New_York"), now),
+        "Mon 20:00 EDT"
+    );
+    assert_eq!(
+        format_start_time(utc(2025, 3, 20, 0, 0), zone("America/New_York"), now),
+        "Wed 19 Mar 20:00 EDT"
+    );
+}
+
+#[test]
+fn countdown_formats() {
+    let now = utc(2025, 4, 24, 12, 0);
+    assert_eq!(countdown(utc(2025, 4, 26, 16, 30), now), "2d 4h");
+    assert_eq!(countdown(utc(2025, 4, 24, 15, 5), now), "3h 05m");
+    assert_eq!(countdown(utc(2025, 4, 24, 12, 12), now), "12m");
+    assert_eq!(countdown(now + chrono::Duration::seconds(30), now), "<1m");
+    assert_eq!(countdown(utc(2025, 4, 24, 11, 0), now), "now");
+}
+
+#[test]
+fn start_times_are_shown_in_the_given_zone() {
+    let upcoming = parse_live_matches(common::UPCOMING).unwrap();
+    let now = utc(2025, 4, 4, 12, 0);
+
+    let utc_times = with_start_times(upcoming.clone(), zone("UTC"), now);
+    let tokyo_times = with_start_times(upcoming, zone("Asia/Tokyo"), now);
+    assert!(utc_times[0].start_time.ends_with("UTC"));
+    assert!(tokyo_times[0].start_time.ends_with("JST"));
+    assert_ne!(utc_times[0].start_time, tokyo_times[0].start_time);
+}
+
+#[test]
+fn market_date_follows_the_match_in_us_eastern() {
+    let now = utc(2025, 4, 24, 12, 0);
+    // 02:00 UTC on the 25th is still the evening of the 24th in New York
+    assert_eq!(
+        market_date(Some(utc(2025, 4, 25, 2, 0)), now),
+        NaiveDate::from_ymd_opt(2025, 4, 24).unwrap()
+    );
+    assert_eq!(
+        market_date(Some(utc(2025, 4, 25, 18, 0)), now),
+        NaiveDate::from_ymd_opt(2025, 4, 25).unwrap()
+    );
+    assert_eq!(market_date(None, now), NaiveDate::from_ymd_opt(2025, 4, 24).unwrap());
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use valo_core::calendar::CalendarFeed;
+use valo_core::server::router;
+use valo_core::settings::{CalendarSettings, FollowSettings};
+use valo_core::snapshot::MatchStore;
+
+async fn serve(token: Option<&str>) -> String {
+    let store = Arc::new(MatchStore::new());
+    let calendar = Arc::new(CalendarFeed::load(None, FollowSettings::default(), CalendarSettings::default()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = router(store, calendar, token.map(str::to_string), address.port());
+    let url = format!("http://{}", address);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn status(url: &str, origin: Option<&str>) -> u16 {
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(origin) = origin {
+        request = request.header("Origin", origin);
+    }
+    request.send().await.unwrap().status().as_u16()
+}
+
+#[tokio::test]
+async fn streams_reject_other_origins_without_a_token() {
+    let url = serve(None).await;
+
+    assert_eq!(status(&format!("{}/events", url), Some("https://example.com")).await, 403);
+    assert_eq!(status(&format!("{}/ws", url), Some("http://localhost:3000")).await, 403);
+    // The overlay page and clients that aren't browser pages
+    assert_eq!(status(&format!("{}/events", url), Some(&url)).await, 200);
+    assert_eq!(status(&format!("{}/events", url), None).await, 200);
+    // Plain JSON endpoints can't be read cross-origin anyway
+    assert_eq!(status(&format!("{}/api/snapshot", url), Some("https://example.com")).await, 200);
+}
+
+#[tokio::test]
+async fn a_token_allows_other_origins() {
+    let url = serve(Some("secret")).await;
+
+    assert_eq!(status(&format!("{}/events", url), Some("https://example.com")).await, 401);
+    assert_eq!(
+        status(&format!("{}/events?token=secret", url), Some("https://example.com")).await,
+        200
+    );
+}
+
+async fn status_for_host(url: &str, host: &str) -> u16 {
+    let request = reqwest::Client::new().get(url).header("Host", host);
+    request.send().await.unwrap().status().as_u16()
+}
+
+#[tokio::test]
+async fn rebound_hostnames_are_rejected_without_a_token() {
+    let url = serve(None).await;
+    let port = url.rsplit(':').next().unwrap();
+
+    // A page on attacker.example whose DNS now points at 127.0.0.1
+    let rebound = format!("attacker.example:{}", port);
+    assert_eq!(status_for_host(&format!("{}/api/snapshot", url), &rebound).await, 403);
+    assert_eq!(status_for_host(&format!("{}/calendar.ics", url), &rebound).await, 403);
+    assert_eq!(status_for_host(&format!("{}/api/snapshot", url), &format!("localhost:{}", port)).await, 200);
+    assert_eq!(status_for_host(&format!("{}/calendar.ics", url), &format!("127.0.0.1:{}", port)).await, 200);
+}
+
+#[tokio::test]
+async fn a_token_allows_other_hostnames() {
+    let url = serve(Some("secret")).await;
+    let port = url.rsplit(':').next().unwrap();
+
+    let host = format!("valo.lan:{}", port);
+    assert_eq!(status_for_host(&format!("{}/api/snapshot?token=secret", url), &host).await, 200);
+}
@@ -0,0 +1,287 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{temp_path, LIVE_SCORE, LIVE_SCORE_EMPTY, POLYMARKET_SEARCH, UPCOMING};
+use std::convert::identity;
+use valo_core::poller::poll_once;
+use valo_core::snapshot::{MatchEvent, MatchStore};
+use valo_core::source::{Api, LiveSource, MatchSource, Recorder, ReplaySource, SourceOptions};
+
+const LIVE_PATH: &str = "/v2/match?q=live_score";
+const UPCOMING_PATH: &str = "/v2/match?q=upcoming";
+const SEARCH_PATH: &str = "/public-search";
+
+async fn live_source() -> (MockServer, LiveSource) {
+    let server = MockServer::start().await;
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+    (server, source)
+}
+
+#[tokio::test]
+async fn live_matches_are_parsed_with_ids() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE);
+
+    let matches = source.live_matches().await.unwrap();
+
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].team1, "Sentinels");
+    assert_eq!(matches[0].score1, "1");
+    assert_eq!(matches[0].current_map, "Bind");
+    assert_eq!(matches[0].match_id, "449012");
+    // No match page: the id falls back to the team slugs
+    assert_eq!(matches[1].match_id, "tl-fnatic");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].query_param("q").as_deref(), Some("live_score"));
+}
+
+#[tokio::test]
+async fn missing_optional_fields_default_to_empty() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE);
+
+    let matches = source.live_matches().await.unwrap();
+
+    assert_eq!(matches[1].flag1, "");
+    assert_eq!(matches[1].team1_round_ct, "");
+    assert_eq!(matches[1].match_series, "");
+}
+
+#[tokio::test]
+async fn empty_segments_mean_no_matches() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE_EMPTY);
+
+    assert!(source.live_matches().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn malformed_live_payloads_are_errors() {
+    let (server, source) = live_source().await;
+
+    server.respond(LIVE_PATH, 200, "{\"status\": \"success\", \"data\": ");
+    assert!(source.live_matches().await.is_err());
+
+    // Segments without the required team and score fields
+    server.respond(
+        LIVE_PATH,
+        200,
+        r#"{"status": "success", "data": {"status": 200, "segments": [{"team1": "Sentinels"}]}}"#,
+    );
+    assert!(source.live_matches().await.is_err());
+
+    server.respond(LIVE_PATH, 200, r#"{"status": "success"}"#);
+    assert!(source.live_matches().await.is_err());
+}
+
+#[tokio::test]
+async fn non_200_live_responses_are_errors() {
+    let (server, source) = live_source().await;
+
+    server.respond(LIVE_PATH, 500, "<html>Internal Server Error</html>");
+    assert!(source.live_matches().await.is_err());
+
+    server.respond(LIVE_PATH, 429, r#"{"error": "rate limited"}"#);
+    assert!(source.live_matches().await.is_err());
+}
+
+#[tokio::test]
+async fn error_statuses_with_parseable_bodies_are_errors() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 500, LIVE_SCORE_EMPTY);
+    server.respond(UPCOMING_PATH, 503, UPCOMING);
+
+    let error = source.live_matches().await.unwrap_err();
+    assert_eq!(error.to_string(), "Live scores returned 500");
+    assert!(source.upcoming_matches().await.is_err());
+}
+
+#[tokio::test]
+async fn unreachable_api_is_an_error() {
+    let server = MockServer::start().await;
+    let url = server.url.clone();
+    drop(server);
+    tokio::task::yield_now().await;
+
+    let source = LiveSource::with_base_urls(&url, &url, None);
+    assert!(source.live_matches().await.is_err());
+}
+
+#[tokio::test]
+async fn odds_come_from_the_matching_market() {
+    let (server, source) = live_source().await;
+    server.respond(SEARCH_PATH, 200, POLYMARKET_SEARCH);
+
+    let odds = source.odds("Sentinels", "100 Thieves", None).await.unwrap();
+
+    assert_eq!(odds.team1_odds, Some(0.62));
+    assert_eq!(odds.team2_odds, Some(0.38));
+    assert_eq!(odds.market_url.as_deref(), Some("https://polymarket.com/val-sen-100t-2025-04-04"));
+
+    let requests = server.requests();
+    assert_eq!(requests[0].query_param("q").as_deref(), Some("Sentinels vs 100 Thieves"));
+    assert_eq!(requests[0].query_param("limit").as_deref(), Some("10"));
+}
+
+#[tokio::test]
+async fn odds_without_a_market_fall_back_to_the_url() {
+    let (server, source) = live_source().await;
+    server.respond(SEARCH_PATH, 200, POLYMARKET_SEARCH);
+
+    let odds = source.odds("Paper Rex", "DRX", None).await.unwrap();
+
+    assert_eq!(odds.team1_odds, None);
+    assert_eq!(odds.team2_odds, None);
+    let url = odds.market_url.unwrap();
+    assert!(url.starts_with("https://polymarket.com/sports/valorant/games/week/1/val-prx-drx-"), "{}", url);
+}
+
+#[tokio::test]
+async fn failed_odds_searches_fall_back_to_the_url() {
+    let (server, source) = live_source().await;
+    server.respond(SEARCH_PATH, 503, "Service Unavailable");
+
+    let odds = source.odds("Sentinels", "100 Thieves", None).await.unwrap();
+
+    assert_eq!(odds.team1_odds, None);
+    assert!(odds.market_url.unwrap().contains("val-sentinels-100t-"));
+}
+
+#[tokio::test]
+async fn malformed_odds_payloads() {
+    let (server, source) = live_source().await;
+
+    server.respond(SEARCH_PATH, 200, "not json");
+    assert!(source.odds("Sentinels", "100 Thieves", None).await.is_err());
+
+    // Unparseable prices are treated as missing
+    server.respond(
+        SEARCH_PATH,
+        200,
+        r#"{"results": [{"question": "Sentinels vs 100 Thieves", "outcomes": [
+            {"title": "Sentinels", "price": "n/a"},
+            {"title": "100 Thieves", "price": "0.4"}
+        ]}]}"#,
+    );
+    let odds = source.odds("Sentinels", "100 Thieves", None).await.unwrap();
+    assert_eq!(odds.team1_odds, None);
+    assert_eq!(odds.team2_odds, Some(0.4));
+}
+
+#[tokio::test]
+async fn poller_diffs_successive_responses() {
+    let (server, source) = live_source().await;
+    let second_map = LIVE_SCORE
+        .replace("\"score1\": \"1\"", "\"score1\": \"2\"")
+        .replace("\"current_map\": \"Bind\"", "\"current_map\": \"Haven\"");
+    server.respond_sequence(LIVE_PATH, &[(200, LIVE_SCORE), (200, &second_map), (200, LIVE_SCORE_EMPTY)]);
+    server.respond(SEARCH_PATH, 200, POLYMARKET_SEARCH);
+    let store = MatchStore::new();
+
+    // The first poll is only a baseline
+    assert!(poll_once(&store, &source, identity).await.unwrap().is_empty());
+
+    let events = poll_once(&store, &source, identity).await.unwrap();
+    assert!(events.contains(&MatchEvent::MapWon {
+        match_id: "449012".to_string(),
+        team: "Sentinels".to_string(),
+        map: "Bind".to_string(),
+        score1: "2".to_string(),
+        score2: "0".to_string(),
+    }));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, MatchEvent::MapStarted { map, .. } if map == "Haven")));
+
+    let events = poll_once(&store, &source, identity).await.unwrap();
+    let ended: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            MatchEvent::MatchEnded { match_id, .. } => Some(match_id.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(ended, ["449012", "tl-fnatic"]);
+}
+
+#[tokio::test]
+async fn poller_keeps_upcoming_matches_soonest_first() {
+    let (server, source) = live_source().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE_EMPTY);
+    server.respond_sequence(UPCOMING_PATH, &[(200, UPCOMING), (500, "")]);
+    let store = MatchStore::new();
+
+    poll_once(&store, &source, identity).await.unwrap();
+    let upcoming = store.snapshot().upcoming;
+    let teams: Vec<_> = upcoming.iter().map(|m| m.team1.as_str()).collect();
+    assert_eq!(teams, ["MIBR", "Cloud9"]);
+    assert_eq!(upcoming[0].starts_at, Some(1743814800));
+
+    // A failed schedule fetch keeps the previous list
+    poll_once(&store, &source, identity).await.unwrap();
+    assert_eq!(store.snapshot().upcoming.len(), 2);
+}
+
+#[tokio::test]
+async fn odds_fallback_uses_the_market_date_of_the_match() {
+    let (server, source) = live_source().await;
+    server.respond(SEARCH_PATH, 500, "");
+
+    // 00:30 UTC on the 6th is the evening of the 5th in New York
+    let odds = source.odds("Cloud9", "Evil Geniuses", Some(1743899400)).await.unwrap();
+    assert!(odds.market_url.unwrap().ends_with("-2025-04-05"));
+}
+
+#[tokio::test]
+async fn recorded_sessions_replay_the_same_data() {
+    let server = MockServer::start().await;
+    server.respond(LIVE_PATH, 200, LIVE_SCORE);
+    server.respond(SEARCH_PATH, 200, POLYMARKET_SEARCH);
+    let path = temp_path("session.jsonl");
+
+    let recorder = Recorder::create(&path).unwrap();
+    let live = LiveSource::with_base_urls(&server.url, &server.url, Some(recorder));
+    let live_matches = live.live_matches().await.unwrap();
+    let live_odds = live.odds("Sentinels", "100 Thieves", None).await.unwrap();
+    drop(live);
+
+    let replay = ReplaySource::load(&path, 1.0).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(replay.live_matches().await.unwrap(), live_matches);
+    let odds = replay.odds("Sentinels", "100 Thieves", None).await.unwrap();
+    assert_eq!(odds.team1_odds, live_odds.team1_odds);
+    assert_eq!(odds.market_url, live_odds.market_url);
+    assert!(replay.odds("Paper Rex", "DRX", None).await.is_err());
+}
+
+#[tokio::test]
+async fn replay_follows_the_recorded_timeline() {
+    let response = |at_ms, body: &str| valo_core::source::RecordedResponse {
+        at_ms,
+        recorded_at: 0,
+        api: Api::LiveScore,
+        query: String::new(),
+        status: 200,
+        body: body.to_string(),
+    };
+    // Ten minutes apart, replayed fast enough to reach the second one immediately
+    let fast = ReplaySource::new(
+        vec![response(1_000, LIVE_SCORE), response(601_000, LIVE_SCORE_EMPTY)],
+        1_000_000.0,
+    );
+    let slow = ReplaySource::new(
+        vec![response(1_000, LIVE_SCORE), response(601_000, LIVE_SCORE_EMPTY)],
+        1.0,
+    );
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+    assert!(fast.live_matches().await.unwrap().is_empty());
+    assert_eq!(slow.live_matches().await.unwrap().len(), 2);
+}
+
+#[test]
+fn default_options_replay_in_real_time() {
+    assert_eq!(SourceOptions::default().speed, 1.0);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{LIVE_SCORE, MATCH_PAGE, TEAM, UPCOMING};
+use valo_core::api::{parse_live_matches, parse_team};
+use valo_core::source::LiveSource;
+use valo_core::teams::{
+    parse_team_links, resolve_logo_url, with_local_upcoming, ProfileCache, TeamRegistry, PROFILE_MAX_AGE_SECS,
+};
+
+const TEAM_PATH: &str = "/v2/team";
+const NOW: i64 = 1743900000;
+
+async fn live_source() -> (MockServer, LiveSource) {
+    let server = MockServer::start().await;
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+    (server, source)
+}
+
+#[test]
+fn logo_urls_are_made_absolute() {
+    assert_eq!(resolve_logo_url("//owcdn.net/img/a.png"), "https://owcdn.net/img/a.png");
+    assert_eq!(resolve_logo_url("/img/vlr/tmp/vlr.png"), "https://www.vlr.gg/img/vlr/tmp/vlr.png");
+    assert_eq!(resolve_logo_url("https://owcdn.net/img/a.png"), "https://owcdn.net/img/a.png");
+    assert_eq!(resolve_logo_url(""), "");
+}
+
+#[test]
+fn registry_knows_teams_by_name_and_learns_logos() {
+    let registry = TeamRegistry::load(None);
+    assert_eq!(registry.resolve_id("Sentinels").as_deref(), Some("2"));
+    assert_eq!(registry.resolve_id("100T").as_deref(), Some("120"));
+    assert_eq!(registry.resolve_id("1034").as_deref(), Some("1034"));
+    assert_eq!(registry.resolve_id("Unknown Squad"), None);
+
+    registry.learn_matches(&parse_live_matches(LIVE_SCORE).unwrap());
+    assert_eq!(registry.find("sentinels").unwrap().logo, "https://owcdn.net/img/sentinels.png");
+    assert_eq!(registry.find("Sentinels").unwrap().tag, "SEN");
+}
+
+#[tokio::test]
+async fn profiles_are_fetched_by_name_or_id() {
+    let (server, source) = live_source().await;
+    server.respond(TEAM_PATH, 200, TEAM);
+    let registry = TeamRegistry::load(None);
+    let cache = ProfileCache::new();
+
+    let profile = cache.get(&source, &registry, "Sentinels", &[], NOW).await.unwrap();
+
+    assert_eq!(server.requests()[0].query_param("id").as_deref(), Some("2"));
+    assert_eq!(profile.name, "Sentinels");
+    assert_eq!(profile.logo, "https://owcdn.net/img/62875027c8e06.png");
+    assert_eq!(profile.roster.len(), 6);
+    assert_eq!(profile.roster[5].role, "head coach");
+    assert_eq!(profile.form, "WWL");
+    assert_eq!(registry.find("Sentinels").unwrap().logo, profile.logo);
+}
+
+#[tokio::test]
+async fn profiles_are_cached() {
+    let (server, source) = live_source().await;
+    server.respond_sequence(TEAM_PATH, &[(200, TEAM), (500, "<html>error</html>")]);
+    let registry = TeamRegistry::load(None);
+    let cache = ProfileCache::new();
+
+    cache.get(&source, &registry, "2", &[], NOW).await.unwrap();
+    cache.get(&source, &registry, "Sentinels", &[], NOW + 60).await.unwrap();
+    assert_eq!(server.requests().len(), 1);
+
+    // Stale and the refresh fails: the cached profile is kept
+    let profile = cache.get(&source, &registry, "2", &[], NOW + PROFILE_MAX_AGE_SECS).await.unwrap();
+    assert_eq!(profile.name, "Sentinels");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn unknown_teams_are_errors() {
+    let (server, source) = live_source().await;
+    server.respond(TEAM_PATH, 200, r#"{"status": "success", "data": {"status": 200, "segments": []}}"#);
+    let registry = TeamRegistry::load(None);
+    let cache = ProfileCache::new();
+
+    assert!(cache.get(&source, &registry, "Unknown Squad", &[], NOW).await.is_err());
+    assert!(server.requests().is_empty());
+    assert!(cache.get(&source, &registry, "999999", &[], NOW).await.is_err());
+}
+
+#[test]
+fn local_upcoming_matches_complete_the_schedule() {
+    let profile = parse_team(&TEAM.replace("\"name\": \"Sentinels\"", "\"name\": \"NRG\"")).unwrap().unwrap();
+    let upcoming = parse_live_matches(UPCOMING).unwrap();
+
+    let profile = with_local_upcoming(profile, &upcoming);
+    let opponents: Vec<_> = profile.upcoming_matches.iter().map(|m| m.opponent.as_str()).collect();
+    assert_eq!(opponents, ["MIBR", "MIBR"]);
+    assert_eq!(profile.upcoming_matches[1].event, "VCT 2025: Americas Stage 1");
+
+    // Already listed by the endpoint
+    let again = with_local_upcoming(profile.clone(), &upcoming);
+    assert_eq!(again.upcoming_matches.len(), 2);
+}
+
+#[test]
+fn match_pages_link_their_teams() {
+    assert_eq!(
+        parse_team_links(MATCH_PAGE),
+        [("2".to_string(), "Sentinels".to_string()), ("120".to_string(), "100 Thieves".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn unknown_teams_are_found_on_their_match_page() {
+    let (server, source) = live_source().await;
+    let source = source.with_vlr_url(&server.url);
+    // A team that isn't built in, playing the fixture's live match
+    let live = LIVE_SCORE.replace("100 Thieves", "Team Heretics");
+    let page = MATCH_PAGE
+        .replace("/team/120/100-thieves", "/team/1001/team-heretics")
+        .replace("100 Thieves", "Team Heretics");
+    server.respond("/449012/sentinels-vs-100-thieves-vct-2025-americas-stage-1-w3", 200, &page);
+    let team = TEAM.replace("\"id\": \"2\"", "\"id\": \"1001\"").replace("Sentinels", "Team Heretics");
+    server.respond(TEAM_PATH, 200, &team);
+    let registry = TeamRegistry::load(None);
+    let cache = ProfileCache::new();
+    let matches = parse_live_matches(&live).unwrap();
+
+    let profile = cache.get(&source, &registry, "Team Heretics", &matches, NOW).await.unwrap();
+
+    assert_eq!(profile.name, "Team Heretics");
+    let team_request = server.requests().into_iter().find(|r| r.path == TEAM_PATH).unwrap();
+    assert_eq!(team_request.query_param("id").as_deref(), Some("1001"));
+    assert_eq!(registry.resolve_id("Team Heretics").as_deref(), Some("1001"));
+}
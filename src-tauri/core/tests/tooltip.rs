@@ -0,0 +1,74 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{LIVE_SCORE, LIVE_SCORE_EMPTY, POLYMARKET_SEARCH};
+use std::convert::identity;
+use valo_core::poller::poll_once;
+use valo_core::snapshot::{MatchSnapshot, MatchStore};
+use valo_core::source::LiveSource;
+use valo_core::template::{Template, Templates};
+use valo_core::format::tray_tooltip;
+
+// Poll the mock APIs once and return the resulting snapshot
+async fn snapshot_for(live_score: &str) -> MatchSnapshot {
+    let server = MockServer::start().await;
+    server.respond("/v2/match", 200, live_score);
+    server.respond("/public-search", 200, POLYMARKET_SEARCH);
+    let source = LiveSource::with_base_urls(&server.url, &server.url, None);
+    let store = MatchStore::new();
+    poll_once(&store, &source, identity).await.unwrap();
+    store.snapshot()
+}
+
+#[tokio::test]
+async fn tooltip_shows_the_first_live_match() {
+    let snapshot = snapshot_for(LIVE_SCORE).await;
+
+    assert_eq!(
+        tray_tooltip(&snapshot, &Templates::default().tooltip),
+        "Valorant: Sentinels vs 100 Thieves | 1 - 0 | Bind"
+    );
+}
+
+#[tokio::test]
+async fn tooltip_without_matches() {
+    let snapshot = snapshot_for(LIVE_SCORE_EMPTY).await;
+
+    assert_eq!(
+        tray_tooltip(&snapshot, &Templates::default().tooltip),
+        "Valorant: No live matches"
+    );
+}
+
+#[tokio::test]
+async fn tooltip_for_upcoming_and_unknown_maps() {
+    let upcoming = LIVE_SCORE.replacen("\"time_until_match\": \"LIVE\"", "\"time_until_match\": \"2h 30m\"", 1);
+    let snapshot = snapshot_for(&upcoming).await;
+    assert_eq!(
+        tray_tooltip(&snapshot, &Templates::default().tooltip),
+        "Valorant: Sentinels vs 100 Thieves | 1 - 0 | 2h 30m"
+    );
+
+    let no_map = LIVE_SCORE.replacen("\"current_map\": \"Bind\"", "\"current_map\": \"\"", 1);
+    let snapshot = snapshot_for(&no_map).await;
+    assert_eq!(
+        tray_tooltip(&snapshot, &Templates::default().tooltip),
+        "Valorant: Sentinels vs 100 Thieves | 1 - 0 | Unknown Map"
+    );
+}
+
+#[tokio::test]
+async fn tooltip_template_uses_polled_odds() {
+    let snapshot = snapshot_for(LIVE_SCORE).await;
+    let template = Template::parse("{tag1} {rounds1}-{rounds2} {tag2}{?odds} ({odds1}/{odds2}){/odds}").unwrap();
+
+    assert_eq!(tray_tooltip(&snapshot, &template), "SEN 7-7 100T (62%/38%)");
+}
+
+#[test]
+fn template_errors_point_at_characters() {
+    let error = Template::parse("Équipe {nope}").unwrap_err();
+
+    assert_eq!(error.position, 7);
+    assert_eq!(error.to_string(), "Unknown placeholder '{nope}' at position 7");
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::mock_server::MockServer;
+use common::{LIVE_SCORE, RESULTS};
+use valo_core::api::parse_results;
+use valo_core::models::MatchResult;
+use valo_core::settings::{WebhookEndpoint, WebhookFormat, WebhookSettings};
+use valo_core::snapshot::{MatchEvent, MatchSnapshot};
+use valo_core::template::Templates;
+use valo_core::webhooks::{deliver, enqueue_event, EndedMatches, NotificationKind, WebhookQueue};
+use serde_json::{json, Value};
+
+fn snapshot() -> MatchSnapshot {
+    MatchSnapshot {
+        updated_at: 1,
+        matches: valo_core::api::parse_live_matches(LIVE_SCORE).unwrap(),
+        odds: Default::default(),
+        upcoming: Vec::new(),
+        offline_since: None,
+    }
+}
+
+fn map_won() -> MatchEvent {
+    MatchEvent::MapWon {
+        match_id: "449012".to_string(),
+        team: "Sentinels".to_string(),
+        map: "Ascent".to_string(),
+        score1: "1".to_string(),
+        score2: "0".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn deliver_posts_the_payload() {
+    let server = MockServer::start().await;
+    server.respond("/hook", 204, "");
+    let client = reqwest::Client::new();
+
+    deliver(&client, &format!("{}/hook", server.url), &json!({ "text": "hi" }))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(serde_json::from_str::<Value>(&requests[0].body).unwrap(), json!({ "text": "hi" }));
+}
+
+#[tokio::test]
+async fn deliver_reports_http_errors() {
+    let server = MockServer::start().await;
+    server.respond("/hook", 500, "");
+    let client = reqwest::Client::new();
+
+    let error = deliver(&client, &format!("{}/hook", server.url), &json!({}))
+        .await
+        .unwrap_err();
+    assert!(error.contains("500"), "{}", error);
+}
+
+#[tokio::test]
+async fn events_are_sent_to_interested_endpoints() {
+    let server = MockServer::start().await;
+    server.respond("/discord", 204, "");
+    server.respond("/json", 200, "");
+    let settings = WebhookSettings {
+        endpoints: vec![
+            WebhookEndpoint {
+                url: format!("{}/discord", server.url),
+                format: WebhookFormat::Discord,
+                events: Vec::new(),
+            },
+            WebhookEndpoint {
+                url: format!("{}/json", server.url),
+                format: WebhookFormat::Json,
+                events: vec![NotificationKind::SeriesWon],
+            },
+        ],
+        ..Default::default()
+    };
+    let mut queue = WebhookQueue::load(None);
+
+    enqueue_event(&mut queue, &settings, &Templates::default().notification, &map_won(), &snapshot());
+    queue.flush_due(&reqwest::Client::new()).await;
+
+    assert!(queue.deliveries.is_empty());
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/discord");
+    let payload: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(payload["embeds"][0]["title"], "Sentinels won Ascent");
+    assert_eq!(payload["embeds"][0]["description"], "Sentinels 1 - 0 100 Thieves");
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_later() {
+    let server = MockServer::start().await;
+    server.respond_sequence("/hook", &[(502, ""), (200, "")]);
+    let client = reqwest::Client::new();
+    let mut queue = WebhookQueue::load(None);
+    queue.push(format!("{}/hook", server.url), json!({ "text": "retry me" }));
+
+    queue.flush_due(&client).await;
+    assert_eq!(queue.deliveries.len(), 1);
+    assert_eq!(queue.deliveries[0].attempts, 1);
+    assert!(queue.deliveries[0].next_attempt_at > chrono::Utc::now().timestamp());
+
+    // Not due yet: nothing is sent
+    queue.flush_due(&client).await;
+    assert_eq!(server.requests().len(), 1);
+
+    queue.deliveries[0].next_attempt_at = 0;
+    queue.flush_due(&client).await;
+    assert!(queue.deliveries.is_empty());
+    assert_eq!(server.requests().len(), 2);
+}
+
+// The results feed's entry for the live fixture's match, teams listed the other way round
+fn final_result(score1: &str, score2: &str) -> MatchResult {
+    let mut result = parse_results(RESULTS).unwrap().remove(0);
+    result.match_id = "449012".to_string();
+    result.team1 = "100 Thieves".to_string();
+    result.team2 = "Sentinels".to_string();
+    result.score1 = score1.to_string();
+    result.score2 = score2.to_string();
+    result
+}
+
+#[test]
+fn series_wins_use_the_final_result() {
+    let template = &Templates::default().notification;
+    // Left the live feed at 1-1, before the decider was counted
+    let mut last_seen = snapshot().matches.remove(0);
+    last_seen.score2 = "1".to_string();
+    let after = MatchSnapshot::default();
+    let mut ended = EndedMatches::default();
+    ended.push(last_seen, 100);
+
+    let notifications = ended.confirm(&[final_result("2", "1")], &after, template, 200);
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::SeriesWon);
+    assert_eq!(notifications[0].title, "100 Thieves won the series");
+    assert_eq!(notifications[0].message, "Sentinels 1 - 2 100 Thieves");
+    assert!(ended.is_empty());
+}
+
+#[test]
+fn matches_missing_from_one_poll_are_not_won() {
+    let template = &Templates::default().notification;
+    let live = snapshot();
+    let mut ended = EndedMatches::default();
+    ended.push(live.matches[0].clone(), 100);
+
+    // Not in the results yet: nothing is sent and the match stays pending
+    assert!(ended.confirm(&[], &MatchSnapshot::default(), template, 200).is_empty());
+    assert!(!ended.is_empty());
+
+    // Back in the live feed: it wasn't over
+    assert!(ended.confirm(&[final_result("0", "2")], &live, template, 300).is_empty());
+    assert!(ended.is_empty());
+}
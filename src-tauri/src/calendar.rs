@@ -0,0 +1,39 @@
+use crate::settings::SettingsState;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use valo_core::calendar::CalendarFeed;
+use valo_core::settings::{CalendarSettings, FollowSettings};
+use valo_core::snapshot::MatchStore;
+
+const EXPORT_FILE: &str = "valorant-matches.ics";
+
+// Tauri command to write the followed matches to an .ics file in the downloads folder
+#[tauri::command]
+pub fn export_calendar(
+    app: AppHandle,
+    calendar: State<'_, Arc<CalendarFeed>>,
+    store: State<'_, Arc<MatchStore>>,
+) -> Result<String, String> {
+    let dir = app.path().download_dir().map_err(|e| e.to_string())?;
+    let path = dir.join(EXPORT_FILE);
+    let contents = calendar.render(&store.snapshot(), chrono::Utc::now());
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
+
+// Tauri command to change the followed teams and events and the calendar options.
+// Reminders and news read them from the settings; the feed keeps its own copy.
+#[tauri::command]
+pub fn update_follow_settings(
+    settings: State<'_, SettingsState>,
+    calendar: State<'_, Arc<CalendarFeed>>,
+    follow: FollowSettings,
+    calendar_settings: CalendarSettings,
+) -> Result<(), String> {
+    settings.update(|s| {
+        s.follow = follow.clone();
+        s.calendar = calendar_settings.clone();
+    })?;
+    calendar.set_settings(follow, calendar_settings);
+    Ok(())
+}
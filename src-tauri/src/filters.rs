@@ -0,0 +1,18 @@
+use crate::settings::SettingsState;
+use valo_core::filters::{FilterPreset, PRESETS};
+use valo_core::settings::FilterSettings;
+
+// Tauri command to list the built-in filter presets
+#[tauri::command]
+pub fn get_filter_presets() -> &'static [FilterPreset] {
+    PRESETS
+}
+
+// Tauri command to change which matches are shown; the tray follows on the next poll
+#[tauri::command]
+pub fn update_filters(
+    settings: tauri::State<'_, SettingsState>,
+    filters: FilterSettings,
+) -> Result<FilterSettings, String> {
+    settings.set_filters(filters).map(|s| s.filters)
+}
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+use tauri::State;
+use valo_core::history::{HistoryDb, HistoryFilter, MatchRecord};
+
+// Tauri command to look back at recorded matches
+#[tauri::command]
+pub fn get_match_history(
+    filter: Option<HistoryFilter>,
+    history: State<'_, Arc<HistoryDb>>,
+) -> Result<Vec<MatchRecord>, String> {
+    history
+        .query(&filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
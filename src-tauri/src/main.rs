@@ -1,377 +1,125 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+mod calendar;
+mod filters;
+mod history;
+mod news;
+mod rankings;
+mod ratings;
+mod scoreboard;
+mod server;
+mod settings;
+mod shortcuts;
+mod teams;
+mod webhooks;
+
+use serde::Serialize;
+use settings::SettingsState;
+use shortcuts::ShortcutStatus;
 use std::sync::{Arc, Mutex};
+use valo_core::api::{get_test_matches, USE_TEST_DATA};
+use valo_core::calendar::CalendarFeed;
+use valo_core::format::tray_tooltip;
+use valo_core::history::{run_history, HistoryDb};
+use valo_core::logos::{parse_logo_request, placeholder_asset, with_logo_srcs, LogoCache, LOGO_SCHEME};
+use valo_core::models::MapScoreboard;
+use valo_core::news::{run_news, NewsFeed, NEWS_INTERVAL};
+use valo_core::player_stats::current_map_scoreboard;
+use valo_core::probability::{self, WinProbability};
+use valo_core::rankings::{run_rankings, RankingCache, RANKINGS_CHECK_INTERVAL, RANKINGS_FILE};
+use valo_core::ratings::{run_ratings, RatingBook, RESULTS_INTERVAL};
+use valo_core::reminders::{run_reminders, ReminderScheduler};
+use valo_core::schedule::with_start_times;
+use valo_core::server::{restart_server, ServerHandle};
+use valo_core::snapshot::{MatchSnapshot, MatchStore};
+use valo_core::source::{self, SharedSource};
+use valo_core::teams::{ProfileCache, TeamRegistry};
+use valo_core::template::{Template, TemplateContext};
+use valo_core::webhooks::{run_webhooks, WebhookQueue};
+use valo_core::{cli, poller, MatchSegment, PolymarketOdds};
 use tauri::{
-    menu::{MenuBuilder, MenuItem},
+    menu::{Menu, MenuBuilder, MenuItem},
     tray::{TrayIcon, TrayIconBuilder, TrayIconEvent},
-    LogicalPosition, Manager, Runtime,
+    AppHandle, Emitter, LogicalPosition, Manager, Runtime, State, UriSchemeResponder,
 };
-use tokio::time::{interval, Duration};
+use tauri_plugin_notification::NotificationExt;
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct ApiResponse {
-    status: String,
-    data: ApiData,
+// The matches the window shows, and since when the network has been down, if it is
+#[derive(Serialize)]
+struct LiveMatches {
+    matches: Vec<MatchSegment>,
+    offline_since: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct ApiData {
-    status: u16,
-    segments: Vec<MatchSegment>,
+// Tauri command to get live matches (called from React). Reads what the poller last
+// stored, so the last matches stay visible while offline.
+#[tauri::command]
+fn get_live_matches(store: State<'_, Arc<MatchStore>>, settings: State<'_, SettingsState>) -> LiveMatches {
+    let snapshot = store.snapshot();
+    LiveMatches {
+        matches: with_logo_srcs(settings.filter().filter(snapshot.matches)),
+        offline_since: snapshot.offline_since,
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct MatchSegment {
+// Tauri command to get Polymarket odds for a match
+#[tauri::command]
+async fn get_polymarket_odds(
     team1: String,
     team2: String,
-    score1: String,
-    score2: String,
-    #[serde(default)]
-    current_map: String,
-    #[serde(default)]
-    match_event: String,
-    #[serde(default)]
-    match_series: String,
-    #[serde(default)]
-    time_until_match: String,
-    #[serde(default)]
-    flag1: String,
-    #[serde(default)]
-    flag2: String,
-    #[serde(default)]
-    team1_logo: String,
-    #[serde(default)]
-    team2_logo: String,
-    #[serde(default)]
-    team1_round_ct: String,
-    #[serde(default)]
-    team1_round_t: String,
-    #[serde(default)]
-    team2_round_ct: String,
-    #[serde(default)]
-    team2_round_t: String,
-    #[serde(default)]
-    map_number: String,
-    #[serde(default)]
-    unix_timestamp: String,
-    #[serde(default)]
-    match_page: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct PolymarketOdds {
-    team1_odds: Option<f64>,
-    team2_odds: Option<f64>,
-    market_url: Option<String>,
-}
-
-// Test mode flag - set to true to use mock data
-const USE_TEST_DATA: bool = false; // Change to false to use real API
-
-fn get_test_matches() -> Vec<MatchSegment> {
-    vec![
-        MatchSegment {
-            team1: "Sentinels".to_string(),
-            team2: "100 Thieves".to_string(),
-            score1: "13".to_string(),
-            score2: "9".to_string(),
-            current_map: "Ascent".to_string(),
-            match_event: "VCT 2025: Americas Stage 1".to_string(),
-            match_series: "Regular Season".to_string(),
-            time_until_match: "LIVE".to_string(),
-            flag1: "flag_us".to_string(),
-            flag2: "flag_us".to_string(),
-            team1_logo: "https://owcdn.net/img/62e7a0e8f1c0b.png".to_string(),
-            team2_logo: "https://owcdn.net/img/62e7a0e8f1c0b.png".to_string(),
-            team1_round_ct: "7".to_string(),
-            team1_round_t: "6".to_string(),
-            team2_round_ct: "5".to_string(),
-            team2_round_t: "4".to_string(),
-            map_number: "1".to_string(),
-            unix_timestamp: "1713996000".to_string(),
-            match_page: "https://www.vlr.gg/12345".to_string(),
-        },
-        MatchSegment {
-            team1: "Fnatic".to_string(),
-            team2: "Team Liquid".to_string(),
-            score1: "7".to_string(),
-            score2: "5".to_string(),
-            current_map: "Bind".to_string(),
-            match_event: "VCT 2025: EMEA Stage 1".to_string(),
-            match_series: "Regular Season".to_string(),
-            time_until_match: "LIVE".to_string(),
-            flag1: String::new(),
-            flag2: String::new(),
-            team1_logo: String::new(),
-            team2_logo: String::new(),
-            team1_round_ct: String::new(),
-            team1_round_t: String::new(),
-            team2_round_ct: String::new(),
-            team2_round_t: String::new(),
-            map_number: "2".to_string(),
-            unix_timestamp: String::new(),
-            match_page: String::new(),
-        },
-        MatchSegment {
-            team1: "Paper Rex".to_string(),
-            team2: "DRX".to_string(),
-            score1: "10".to_string(),
-            score2: "8".to_string(),
-            current_map: "Icebox".to_string(),
-            match_event: "VCT 2025: Pacific Stage 1".to_string(),
-            match_series: "Regular Season".to_string(),
-            time_until_match: "LIVE".to_string(),
-            flag1: String::new(),
-            flag2: String::new(),
-            team1_logo: String::new(),
-            team2_logo: String::new(),
-            team1_round_ct: String::new(),
-            team1_round_t: String::new(),
-            team2_round_ct: String::new(),
-            team2_round_t: String::new(),
-            map_number: "1".to_string(),
-            unix_timestamp: String::new(),
-            match_page: String::new(),
-        },
-        MatchSegment {
-            team1: "LOUD".to_string(),
-            team2: "KRÜ Esports".to_string(),
-            score1: "6".to_string(),
-            score2: "6".to_string(),
-            current_map: "Lotus".to_string(),
-            match_event: "VCT 2025: Americas Stage 1".to_string(),
-            match_series: "Regular Season".to_string(),
-            time_until_match: "LIVE".to_string(),
-            flag1: String::new(),
-            flag2: String::new(),
-            team1_logo: String::new(),
-            team2_logo: String::new(),
-            team1_round_ct: String::new(),
-            team1_round_t: String::new(),
-            team2_round_ct: String::new(),
-            team2_round_t: String::new(),
-            map_number: "3".to_string(),
-            unix_timestamp: String::new(),
-            match_page: String::new(),
-        },
-        MatchSegment {
-            team1: "G2 Esports".to_string(),
-            team2: "KOI".to_string(),
-            score1: "12".to_string(),
-            score2: "11".to_string(),
-            current_map: "Split".to_string(),
-            match_event: "VCT 2025: EMEA Stage 1".to_string(),
-            match_series: "Regular Season".to_string(),
-            time_until_match: "LIVE".to_string(),
-            flag1: String::new(),
-            flag2: String::new(),
-            team1_logo: String::new(),
-            team2_logo: String::new(),
-            team1_round_ct: String::new(),
-            team1_round_t: String::new(),
-            team2_round_ct: String::new(),
-            team2_round_t: String::new(),
-            map_number: "2".to_string(),
-            unix_timestamp: String::new(),
-            match_page: String::new(),
-        },
-    ]
-}
-
-async fn fetch_live_matches() -> Result<Vec<MatchSegment>, Box<dyn std::error::Error>> {
-    // Use test data if flag is set
-    if USE_TEST_DATA {
-        return Ok(get_test_matches());
-    }
-
-    // Otherwise fetch from real API
-    let url = "https://vlrggapi.vercel.app/v2/match?q=live_score";
-    let response = reqwest::get(url).await?;
-    let api_response: ApiResponse = response.json().await?;
-
-    Ok(api_response.data.segments)
-}
-
-fn format_match_text(segment: &MatchSegment) -> String {
-    let score = format!("{} - {}", segment.score1, segment.score2);
-    let teams = format!("{} vs {}", segment.team1, segment.team2);
-    let map = if segment.current_map.is_empty() {
-        "Unknown Map".to_string()
-    } else {
-        segment.current_map.clone()
-    };
-    let status = if segment.time_until_match == "LIVE" {
-        "LIVE".to_string()
-    } else if !segment.time_until_match.is_empty() {
-        segment.time_until_match.clone()
-    } else {
-        "".to_string()
-    };
-
-    if status == "LIVE" {
-        format!("{} | {} | {}", teams, score, map)
-    } else if !status.is_empty() {
-        format!("{} | {} | {}", teams, score, status)
-    } else {
-        format!("{} | {}", teams, score)
-    }
+    starts_at: Option<i64>,
+    source: State<'_, SharedSource>,
+) -> Result<PolymarketOdds, String> {
+    source
+        .odds(&team1, &team2, starts_at)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-// Convert team name to Polymarket slug format (lowercase, remove spaces, common abbreviations)
-fn team_to_slug(team: &str) -> String {
-    let team_lower = team.to_lowercase();
-    // Common team abbreviations mapping
-    let abbreviations: HashMap<&str, &str> = [
-        ("100 thieves", "100t"),
-        ("100t", "100t"),
-        ("mibr", "mibr"),
-        ("nrg", "nrg"),
-        ("g2 esports", "g2"),
-        ("g2", "g2"),
-        ("sentinels", "sentinels"),
-        ("fnatic", "fnatic"),
-        ("team liquid", "tl"),
-        ("paper rex", "prx"),
-        ("loud", "loud"),
-        ("kru esports", "kru"),
-        ("kru", "kru"),
-        ("koi", "koi"),
-        ("drx", "drx"),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-    
-    if let Some(abbr) = abbreviations.get(team_lower.as_str()) {
-        return abbr.to_string();
-    }
-    
-    // Otherwise, convert to slug: lowercase, remove spaces and special chars
-    team_lower
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect()
-}
-
-// Format date for Polymarket URL (YYYY-MM-DD)
-fn format_date_for_polymarket() -> String {
-    use chrono::Local;
-    Local::now().format("%Y-%m-%d").to_string()
-}
-
-// Fetch Polymarket odds for a match
-async fn fetch_polymarket_odds(team1: &str, team2: &str) -> Result<PolymarketOdds, Box<dyn std::error::Error>> {
-    let team1_slug = team_to_slug(team1);
-    let team2_slug = team_to_slug(team2);
-    let date = format_date_for_polymarket();
-    
-    // Try searching for the market using team names
-    let search_query = format!("{} vs {}", team1, team2);
-    let search_url = format!(
-        "https://gamma-api.polymarket.com/public-search?q={}&limit=10",
-        urlencoding::encode(&search_query)
-    );
-    
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&search_url)
-        .header("User-Agent", "Valorant-Menubar-App")
-        .send()
-        .await?;
-    
-    if !response.status().is_success() {
-        // If search fails, return empty odds with URL
-        let market_url = format!("https://polymarket.com/sports/valorant/games/week/1/val-{}-{}-{}", 
-            team1_slug, team2_slug, date);
-        return Ok(PolymarketOdds {
-            team1_odds: None,
-            team2_odds: None,
-            market_url: Some(market_url),
-        });
-    }
-    
-    let search_results: serde_json::Value = response.json().await?;
-    
-    // Try to find a market matching the teams
-    if let Some(results) = search_results.get("results").and_then(|r| r.as_array()) {
-        for result in results {
-            if let Some(question) = result.get("question").and_then(|q| q.as_str()) {
-                let question_lower = question.to_lowercase();
-                if question_lower.contains(&team1.to_lowercase()) && 
-                   question_lower.contains(&team2.to_lowercase()) {
-                    // Found a matching market
-                    if let Some(outcomes) = result.get("outcomes").and_then(|o| o.as_array()) {
-                        let mut team1_odds = None;
-                        let mut team2_odds = None;
-                        
-                        for outcome in outcomes {
-                            if let (Some(title), Some(price_str)) = (
-                                outcome.get("title").and_then(|t| t.as_str()),
-                                outcome.get("price").and_then(|p| p.as_str()),
-                            ) {
-                                let price = price_str.parse::<f64>().ok();
-                                let title_lower = title.to_lowercase();
-                                
-                                if title_lower.contains(&team1.to_lowercase()) {
-                                    team1_odds = price;
-                                } else if title_lower.contains(&team2.to_lowercase()) {
-                                    team2_odds = price;
-                                }
-                            }
-                        }
-                        
-                        let slug = result.get("slug").and_then(|s| s.as_str()).map(|s| s.to_string());
-                        let market_url = slug.map(|s| format!("https://polymarket.com/{}", s))
-                            .or_else(|| Some(format!("https://polymarket.com/sports/valorant/games/week/1/val-{}-{}-{}", 
-                                team1_slug, team2_slug, date)));
-                        
-                        return Ok(PolymarketOdds {
-                            team1_odds,
-                            team2_odds,
-                            market_url,
-                        });
-                    }
-                }
-            }
-        }
-    }
-    
-    // If no market found, return URL only
-    let market_url = format!("https://polymarket.com/sports/valorant/games/week/1/val-{}-{}-{}", 
-        team1_slug, team2_slug, date);
-    Ok(PolymarketOdds {
-        team1_odds: None,
-        team2_odds: None,
-        market_url: Some(market_url),
-    })
-}
-
-// Tauri command to get live matches (called from React)
+// Tauri command to get per-player stats for the map a live match is on, from its vlr.gg page
 #[tauri::command]
-async fn get_live_matches() -> Result<Vec<MatchSegment>, String> {
-    fetch_live_matches().await.map_err(|e| e.to_string())
+async fn get_player_stats(
+    segment: MatchSegment,
+    source: State<'_, SharedSource>,
+) -> Result<MapScoreboard, String> {
+    current_map_scoreboard(source.as_ref(), &segment)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-// Dummy Polymarket odds for UI preview (e.g. "Will Jesus Christ return before 2027?" — 4% Yes / 96% No)
-fn get_dummy_polymarket_odds() -> PolymarketOdds {
-    PolymarketOdds {
-        team1_odds: Some(0.04),
-        team2_odds: Some(0.96),
-        market_url: Some("https://polymarket.com/event/will-jesus-christ-return-before-2027".to_string()),
-    }
+// Tauri command to estimate win chances from the round state and compare them with the odds.
+// Team ratings set the starting point, so matches without a market still get a prediction.
+#[tauri::command]
+fn get_win_probability(
+    segment: MatchSegment,
+    odds: Option<PolymarketOdds>,
+    ratings: State<'_, Arc<RatingBook>>,
+) -> WinProbability {
+    let prior = ratings.map_chance(&segment.team1, &segment.team2);
+    probability::estimate_with_prior(&segment, odds.as_ref(), prior)
 }
 
-// Tauri command to get Polymarket odds for a match
-#[tauri::command]
-async fn get_polymarket_odds(team1: String, team2: String) -> Result<PolymarketOdds, String> {
-    if USE_TEST_DATA {
-        return Ok(get_dummy_polymarket_odds());
-    }
-    fetch_polymarket_odds(&team1, &team2).await.map_err(|e| e.to_string())
+// Answer a `logo` scheme request from the logo cache, which downloads logos it doesn't have
+fn serve_logo<R: Runtime>(app: &AppHandle<R>, request: tauri::http::Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let path = request.uri().path_and_query().map(|p| p.as_str().to_string()).unwrap_or_default();
+    let logos = app.try_state::<Arc<LogoCache>>().map(|state| state.inner().clone());
+    tauri::async_runtime::spawn(async move {
+        let asset = match logos {
+            Some(logos) => logos.serve(&path).await,
+            None => placeholder_asset(&parse_logo_request(&path).0),
+        };
+        let response = tauri::http::Response::builder()
+            .header(tauri::http::header::CONTENT_TYPE, asset.content_type)
+            .header(tauri::http::header::CACHE_CONTROL, "max-age=3600")
+            .body(asset.bytes)
+            .unwrap_or_else(|e| {
+                eprintln!("Error building logo response: {}", e);
+                let mut response = tauri::http::Response::new(Vec::new());
+                *response.status_mut() = tauri::http::StatusCode::INTERNAL_SERVER_ERROR;
+                response
+            });
+        responder.respond(response);
+    });
 }
 
 // Position the window near the menu bar (top-right)
@@ -397,12 +145,142 @@ fn position_window_near_menu_bar<R: Runtime>(window: &tauri::WebviewWindow<R>) {
     }
 }
 
+// Show the popover near the menu bar, creating it if needed
+fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        // Show and position window
+        position_window_near_menu_bar(&window);
+        let _ = window.show();
+        let _ = window.set_focus();
+    } else {
+        // Create window if it doesn't exist
+        if let Some(window_config) = app.config().app.windows.iter()
+            .find(|w| w.label == "main") {
+            if let Ok(window) = tauri::WebviewWindowBuilder::from_config(app, window_config)
+                .and_then(|b| b.build()) {
+                // Position window near menu bar
+                position_window_near_menu_bar(&window);
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+    }
+}
+
+// Show the popover, or hide it if it is already visible.
+// Shared by the tray icon click and the global toggle shortcut.
+fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+    match app.get_webview_window("main") {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            // Hide window if already visible
+            let _ = window.hide();
+        }
+        _ => show_main_window(app),
+    }
+}
+
+// Tray menu: one item per live match, then the fixed actions
+fn build_tray_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    snapshot: &MatchSnapshot,
+    template: &Template,
+) -> tauri::Result<Menu<R>> {
+    let mut builder = MenuBuilder::new(app);
+    for segment in &snapshot.matches {
+        let text = template.render(&TemplateContext::new(segment, snapshot.odds.get(&segment.match_id)));
+        let id = format!("match:{}", segment.match_id);
+        builder = builder.item(&MenuItem::with_id(app, id, text, true, None::<&str>)?);
+    }
+    if !snapshot.matches.is_empty() {
+        builder = builder.separator();
+    }
+    builder
+        .item(&MenuItem::with_id(app, "scoreboard", "Toggle Scoreboard", true, None::<&str>)?)
+        .separator()
+        .item(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)
+        .build()
+}
+
 fn main() {
+    // Headless mode: reuse the fetchers and formatting without starting the app
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--cli") {
+        std::process::exit(cli::run(&args[1..]));
+    }
+
+    // Live APIs by default; VALO_RECORD / VALO_REPLAY record or replay a session
+    let source = match source::create_source(&source::SourceOptions::from_env()) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error loading match source: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![get_live_matches, get_polymarket_odds])
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .manage(source)
+        .manage(ShortcutStatus::default())
+        .manage(ServerHandle::default())
+        .manage(Arc::new(MatchStore::new()))
+        .register_asynchronous_uri_scheme_protocol(LOGO_SCHEME, |ctx, request, responder| {
+            serve_logo(ctx.app_handle(), request, responder)
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_live_matches,
+            get_polymarket_odds,
+            get_win_probability,
+            get_player_stats,
+            settings::get_settings,
+            settings::get_settings_errors,
+            scoreboard::toggle_scoreboard,
+            scoreboard::get_pinned_matches,
+            scoreboard::set_match_pinned,
+            shortcuts::get_shortcut_conflicts,
+            shortcuts::update_shortcuts,
+            server::update_server_settings,
+            webhooks::test_webhook,
+            history::get_match_history,
+            ratings::get_team_ratings,
+            ratings::get_rating_prediction,
+            rankings::get_rankings,
+            teams::get_team_profile,
+            news::get_news,
+            calendar::export_calendar,
+            calendar::update_follow_settings,
+            filters::get_filter_presets,
+            filters::update_filters
+        ])
         .setup(|app| {
             let app_handle = app.handle().clone();
 
+            // Load persisted settings and register the global shortcuts
+            let settings_state = settings::load_state(&app_handle);
+            let settings = settings_state.get();
+            let templates = settings_state.templates().clone();
+            app.manage(settings_state);
+            shortcuts::apply_shortcuts(&app_handle, &app.state::<ShortcutStatus>(), &settings.shortcuts);
+
+            // Calendar feed for followed matches, served by the local server
+            let calendar_path = app.path().app_data_dir().ok().map(|dir| dir.join("calendar.json"));
+            let calendar = Arc::new(CalendarFeed::load(
+                calendar_path,
+                settings.follow.clone(),
+                settings.calendar.clone(),
+            ));
+            app.manage(calendar.clone());
+
+            // Start the local server if enabled
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let server_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let handle = server_handle.state::<ServerHandle>();
+                if let Err(e) = restart_server(store, calendar, &handle, &settings.server).await {
+                    eprintln!("Error starting local server: {}", e);
+                }
+            });
+
             // Create tray icon
             let icon = app_handle.default_window_icon().cloned();
             let mut builder = TrayIconBuilder::new().tooltip("Valorant: Loading...");
@@ -411,11 +289,9 @@ fn main() {
                 builder = builder.icon(icon);
             }
 
-            // Create a minimal menu (just quit option) since we're using custom React window
-            // The React window will show all the match information
-            let minimal_menu = MenuBuilder::new(&app_handle)
-                .item(&MenuItem::with_id(&app_handle, "quit", "Quit", true, None::<&str>)?)
-                .build()?;
+            // Create a minimal menu since we're using custom React window; live matches
+            // are added as they are polled. The React window will show all the match information
+            let minimal_menu = build_tray_menu(&app_handle, &MatchSnapshot::default(), &templates.tray_item)?;
             builder = builder.menu(&minimal_menu);
 
             // Store tray reference (populated after build)
@@ -429,32 +305,7 @@ fn main() {
                         match event {
                             TrayIconEvent::Click { .. } => {
                                 // On click, open/show the React popover window
-                                let app = tray_icon.app_handle();
-                                
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let is_visible = window.is_visible().unwrap_or(false);
-                                    if is_visible {
-                                        // Hide window if already visible
-                                        let _ = window.hide();
-                                    } else {
-                                        // Show and position window
-                                        position_window_near_menu_bar(&window);
-                                        let _ = window.show();
-                                        let _ = window.set_focus();
-                                    }
-                                } else {
-                                    // Create window if it doesn't exist
-                                    if let Some(window_config) = app.config().app.windows.iter()
-                                        .find(|w| w.label == "main") {
-                                        if let Ok(window) = tauri::WebviewWindowBuilder::from_config(app, window_config)
-                                            .and_then(|b| b.build()) {
-                                            // Position window near menu bar
-                                            position_window_near_menu_bar(&window);
-                                            let _ = window.show();
-                                            let _ = window.set_focus();
-                                        }
-                                    }
-                                }
+                                toggle_main_window(tray_icon.app_handle());
                             }
                             TrayIconEvent::Enter { .. } => {
                                 // Mouse entered tray icon - menu will stay open while hovering
@@ -475,10 +326,19 @@ fn main() {
                 .on_menu_event({
                     move |app, event| {
                         match event.id.as_ref() {
+                            "scoreboard" => {
+                                scoreboard::toggle_scoreboard_window(app);
+                            }
                             "quit" => {
                                 app.exit(0);
                             }
-                            _ => {}
+                            id => {
+                                // Live match item: open the popover on that match
+                                if let Some(match_id) = id.strip_prefix("match:") {
+                                    show_main_window(app);
+                                    let _ = app.emit("select-match", match_id);
+                                }
+                            }
                         }
                     }
                 })
@@ -490,7 +350,7 @@ fn main() {
             // Update tooltip with match info
             let tooltip_text = if USE_TEST_DATA {
                 if let Some(first_match) = get_test_matches().first() {
-                    format!("Valorant: {}", format_match_text(first_match))
+                    templates.tooltip.render(&TemplateContext::new(first_match, None))
                 } else {
                     "Valorant: No matches".to_string()
                 }
@@ -499,25 +359,138 @@ fn main() {
             };
             let _ = tray.set_tooltip(Some(tooltip_text.as_str()));
 
-            // Set up periodic tooltip update every 30 seconds
+            // Deliver match events to the configured webhooks
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let source = app.state::<SharedSource>().inner().clone();
+            let queue_path = app.path().app_data_dir().ok().map(|dir| dir.join("webhook_queue.json"));
+            let webhook_settings = app_handle.clone();
+            tauri::async_runtime::spawn(run_webhooks(
+                store,
+                source,
+                WebhookQueue::load(queue_path),
+                templates.notification.clone(),
+                move || webhook_settings.state::<SettingsState>().get().webhooks,
+            ));
+
+            // Record match events into the history database
+            let history_path = app.path().app_data_dir().ok().map(|dir| dir.join("history.sqlite3"));
+            let history = match history_path.map(|path| HistoryDb::open(&path)) {
+                Some(Ok(db)) => db,
+                Some(Err(e)) => {
+                    eprintln!("Error opening match history, keeping it in memory: {}", e);
+                    HistoryDb::open_in_memory()?
+                }
+                None => HistoryDb::open_in_memory()?,
+            };
+            let history = Arc::new(history);
+            app.manage(history.clone());
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            tauri::async_runtime::spawn(run_history(store, history));
+
+            // Rate teams from the results feed
+            let ratings_path = app.path().app_data_dir().ok().map(|dir| dir.join("ratings.json"));
+            let ratings = Arc::new(RatingBook::load(ratings_path));
+            app.manage(ratings.clone());
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let source = app.state::<SharedSource>().inner().clone();
+            tauri::async_runtime::spawn(run_ratings(store, source, ratings, RESULTS_INTERVAL));
+
+            // Keep the regional rankings shown on match cards up to date
+            let rankings_path = app.path().app_data_dir().ok().map(|dir| dir.join(RANKINGS_FILE));
+            let rankings = Arc::new(RankingCache::load(rankings_path));
+            app.manage(rankings.clone());
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let source = app.state::<SharedSource>().inner().clone();
+            tauri::async_runtime::spawn(run_rankings(store, source, rankings, RANKINGS_CHECK_INTERVAL));
+
+            // Logos downloaded once and served to the window through the logo scheme
+            let logos_dir = app.path().app_data_dir().ok().map(|dir| dir.join("logos"));
+            app.manage(Arc::new(LogoCache::load(logos_dir)));
+
+            // Team ids and logos for team profiles, learned from the matches that are polled
+            let teams_path = app.path().app_data_dir().ok().map(|dir| dir.join("teams.json"));
+            let registry = Arc::new(TeamRegistry::load(teams_path));
+            app.manage(registry.clone());
+            app.manage(Arc::new(ProfileCache::new()));
+
+            // Notify before followed matches start
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let reminders_path = app.path().app_data_dir().ok().map(|dir| dir.join("reminders.json"));
+            let reminder_settings = app_handle.clone();
+            let notifier = app_handle.clone();
+            tauri::async_runtime::spawn(run_reminders(
+                store,
+                ReminderScheduler::load(reminders_path),
+                move || reminder_settings.state::<SettingsState>().get(),
+                move |reminder, now| {
+                    let result = notifier
+                        .notification()
+                        .builder()
+                        .title(reminder.title())
+                        .body(reminder.body(now))
+                        .show();
+                    if let Err(e) = result {
+                        eprintln!("Error showing reminder for {}: {}", reminder.match_id, e);
+                    }
+                },
+            ));
+
+            // Headlines, with a notification when a followed team is in a new one
+            let news_path = app.path().app_data_dir().ok().map(|dir| dir.join("news.json"));
+            let news = Arc::new(NewsFeed::load(news_path));
+            app.manage(news.clone());
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let source = app.state::<SharedSource>().inner().clone();
+            let news_settings = app_handle.clone();
+            let notifier = app_handle.clone();
+            tauri::async_runtime::spawn(run_news(
+                store,
+                source,
+                news,
+                move || news_settings.state::<SettingsState>().get(),
+                move |article, teams| {
+                    let result = notifier
+                        .notification()
+                        .builder()
+                        .title(format!("{} in the news", teams.join(", ")))
+                        .body(&article.title)
+                        .show();
+                    if let Err(e) = result {
+                        eprintln!("Error showing news notification: {}", e);
+                    }
+                },
+                NEWS_INTERVAL,
+            ));
+
+            // Poll matches every 30 seconds, updating the shared snapshot, tooltip and tray menu
             // The React window will handle its own refresh via the get_live_matches command
             let tray_for_tooltip = tray.clone();
+            let store = app.state::<Arc<MatchStore>>().inner().clone();
+            let source = app.state::<SharedSource>().inner().clone();
+            let menu_handle = app_handle.clone();
+            let prepare_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                let mut interval = interval(Duration::from_secs(30));
-                loop {
-                    interval.tick().await;
-                    match fetch_live_matches().await {
-                        Ok(matches) => {
-                            if let Some(first_match) = matches.first() {
-                                let tooltip_text = format!("Valorant: {}", format_match_text(first_match));
-                                let _ = tray_for_tooltip.set_tooltip(Some(tooltip_text.as_str()));
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Error fetching matches: {}", e);
+                // Start times in the timezone from the settings, and each team's rank
+                let prepare = |matches| {
+                    let zone = prepare_handle.state::<SettingsState>().display_zone();
+                    let rankings = prepare_handle.state::<Arc<RankingCache>>();
+                    rankings.annotate(with_start_times(matches, zone, chrono::Utc::now()))
+                };
+                poller::run_poller(&store, source.as_ref(), poller::POLL_INTERVAL, prepare, |snapshot| {
+                    registry.learn_matches(&snapshot.matches);
+                    registry.learn_matches(&snapshot.upcoming);
+                    // The tray only shows matches allowed by the filter settings
+                    let snapshot = menu_handle.state::<SettingsState>().filter().apply(snapshot);
+                    let tooltip_text = tray_tooltip(&snapshot, &templates.tooltip);
+                    let _ = tray_for_tooltip.set_tooltip(Some(tooltip_text.as_str()));
+                    match build_tray_menu(&menu_handle, &snapshot, &templates.tray_item) {
+                        Ok(menu) => {
+                            let _ = tray_for_tooltip.set_menu(Some(menu));
                         }
+                        Err(e) => eprintln!("Error updating tray menu: {}", e),
                     }
-                }
+                })
+                .await;
             });
 
             Ok(())
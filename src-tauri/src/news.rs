@@ -0,0 +1,14 @@
+use crate::settings::SettingsState;
+use std::sync::Arc;
+use tauri::State;
+use valo_core::news::{NewsFeed, NewsItem};
+
+// Tauri command to get the cached headlines, optionally only those about followed teams
+#[tauri::command]
+pub fn get_news(
+    followed_only: bool,
+    feed: State<'_, Arc<NewsFeed>>,
+    settings: State<'_, SettingsState>,
+) -> Vec<NewsItem> {
+    feed.articles(&settings.get().follow.teams, followed_only)
+}
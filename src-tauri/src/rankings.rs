@@ -0,0 +1,22 @@
+use std::sync::Arc;
+use tauri::State;
+use valo_core::models::RankedTeam;
+use valo_core::rankings::RankingCache;
+use valo_core::source::SharedSource;
+use valo_core::teams::TeamRegistry;
+
+// Tauri command to get a region's vlr.gg ranking, e.g. "eu", cached for a day
+#[tauri::command]
+pub async fn get_rankings(
+    region: String,
+    source: State<'_, SharedSource>,
+    rankings: State<'_, Arc<RankingCache>>,
+    registry: State<'_, Arc<TeamRegistry>>,
+) -> Result<Vec<RankedTeam>, String> {
+    let teams = rankings
+        .get(source.as_ref(), &region, chrono::Utc::now().timestamp())
+        .await
+        .map_err(|e| e.to_string())?;
+    registry.learn_rankings(&teams);
+    Ok(teams)
+}
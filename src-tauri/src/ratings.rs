@@ -0,0 +1,20 @@
+use std::sync::Arc;
+use tauri::State;
+use valo_core::ratings::{RatingBook, RatingPrediction, TeamRating};
+
+// Tauri command to list team ratings, highest first
+#[tauri::command]
+pub fn get_team_ratings(ratings: State<'_, Arc<RatingBook>>) -> Vec<TeamRating> {
+    ratings.ratings()
+}
+
+// Tauri command to get the rating-based expectation for any pairing
+#[tauri::command]
+pub fn get_rating_prediction(
+    team1: String,
+    team2: String,
+    best_of: Option<u32>,
+    ratings: State<'_, Arc<RatingBook>>,
+) -> RatingPrediction {
+    ratings.predict(&team1, &team2, best_of.unwrap_or(3))
+}
@@ -0,0 +1,179 @@
+use crate::settings::{SettingsState, WindowGeometry};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    WindowEvent,
+};
+use tokio::time::{sleep, Duration};
+
+const SCOREBOARD_LABEL: &str = "scoreboard";
+const DEFAULT_WIDTH: f64 = 260.0;
+const DEFAULT_HEIGHT: f64 = 90.0;
+// Wait for a drag or resize to settle before writing the geometry to disk
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+fn monitor_name(monitor: &Monitor) -> String {
+    monitor.name().cloned().unwrap_or_else(|| "default".to_string())
+}
+
+// Bottom-right corner of the primary monitor, clear of the dock/taskbar
+fn default_geometry<R: Runtime>(app: &AppHandle<R>) -> Option<(String, WindowGeometry)> {
+    let monitor = app.primary_monitor().ok().flatten()?;
+    let scale_factor = monitor.scale_factor();
+    let size = monitor.size();
+    let geometry = WindowGeometry {
+        x: size.width as f64 / scale_factor - DEFAULT_WIDTH - 20.0,
+        y: size.height as f64 / scale_factor - DEFAULT_HEIGHT - 100.0,
+        width: DEFAULT_WIDTH,
+        height: DEFAULT_HEIGHT,
+    };
+    Some((monitor_name(&monitor), geometry))
+}
+
+// Saved geometry for the last used monitor, or any other connected monitor we have a position for
+fn saved_geometry<R: Runtime>(app: &AppHandle<R>) -> Option<WindowGeometry> {
+    let scoreboard = app.state::<SettingsState>().get().scoreboard;
+    let connected: Vec<String> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(monitor_name)
+        .collect();
+
+    scoreboard
+        .last_monitor
+        .iter()
+        .chain(connected.iter())
+        .filter(|name| connected.contains(name))
+        .find_map(|name| scoreboard.geometry.get(name).copied())
+}
+
+fn current_geometry<R: Runtime>(window: &WebviewWindow<R>) -> Option<(String, WindowGeometry)> {
+    let monitor = window.current_monitor().ok().flatten()?;
+    let scale_factor = monitor.scale_factor();
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale_factor);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale_factor);
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    Some((monitor_name(&monitor), geometry))
+}
+
+fn save_geometry<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some((monitor, geometry)) = current_geometry(window) else {
+        return;
+    };
+    let settings = window.state::<SettingsState>();
+    let result = settings.update(|s| {
+        s.scoreboard.geometry.insert(monitor.clone(), geometry);
+        s.scoreboard.last_monitor = Some(monitor);
+    });
+    if let Err(e) = result {
+        eprintln!("Error saving scoreboard position: {}", e);
+    }
+}
+
+fn build_scoreboard<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<WebviewWindow<R>> {
+    let geometry = saved_geometry(app)
+        .or_else(|| default_geometry(app).map(|(_, geometry)| geometry))
+        .unwrap_or(WindowGeometry {
+            x: 100.0,
+            y: 100.0,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+        });
+
+    // Unlike the popover, the scoreboard stays on top and does not hide on blur,
+    // so it remains visible over games and while streaming
+    let window = WebviewWindowBuilder::new(app, SCOREBOARD_LABEL, WebviewUrl::App("index.html".into()))
+        .title("Valorant Scoreboard")
+        .inner_size(geometry.width, geometry.height)
+        .min_inner_size(180.0, 48.0)
+        .position(geometry.x, geometry.y)
+        .resizable(true)
+        .decorations(false)
+        .always_on_top(true)
+        .visible_on_all_workspaces(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .build()?;
+
+    // Debounce geometry writes: only the last move/resize in a burst is saved
+    let generation = Arc::new(AtomicU64::new(0));
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+            let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+            let generation = generation.clone();
+            let window = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                sleep(SAVE_DELAY).await;
+                if generation.load(Ordering::SeqCst) == current {
+                    save_geometry(&window);
+                }
+            });
+        }
+    });
+
+    Ok(window)
+}
+
+pub fn show_scoreboard<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(SCOREBOARD_LABEL) {
+        let _ = window.show();
+        return;
+    }
+    if let Err(e) = build_scoreboard(app) {
+        eprintln!("Error creating scoreboard window: {}", e);
+    }
+}
+
+// Open the scoreboard, or close it if it is already open
+pub fn toggle_scoreboard_window<R: Runtime>(app: &AppHandle<R>) {
+    match app.get_webview_window(SCOREBOARD_LABEL) {
+        Some(window) => {
+            let _ = window.close();
+        }
+        None => show_scoreboard(app),
+    }
+}
+
+// Tauri command to open/close the scoreboard window
+#[tauri::command]
+pub fn toggle_scoreboard<R: Runtime>(app: AppHandle<R>) {
+    toggle_scoreboard_window(&app);
+}
+
+// Tauri command to list the match ids pinned to the scoreboard
+#[tauri::command]
+pub fn get_pinned_matches(settings: tauri::State<'_, SettingsState>) -> Vec<String> {
+    settings.get().scoreboard.pinned_matches
+}
+
+// Tauri command to pin or unpin a match; pinning opens the scoreboard
+#[tauri::command]
+pub fn set_match_pinned<R: Runtime>(
+    app: AppHandle<R>,
+    settings: tauri::State<'_, SettingsState>,
+    match_id: String,
+    pinned: bool,
+) -> Result<Vec<String>, String> {
+    let updated = settings.update(|s| {
+        let pinned_matches = &mut s.scoreboard.pinned_matches;
+        pinned_matches.retain(|id| id != &match_id);
+        if pinned {
+            pinned_matches.push(match_id.clone());
+        }
+    })?;
+    let pinned_matches = updated.scoreboard.pinned_matches;
+
+    let _ = app.emit("pinned-matches-changed", &pinned_matches);
+    if pinned {
+        show_scoreboard(&app);
+    }
+    Ok(pinned_matches)
+}
@@ -0,0 +1,19 @@
+use crate::settings::SettingsState;
+use std::sync::Arc;
+use valo_core::calendar::CalendarFeed;
+use valo_core::server::{restart_server, ServerHandle};
+use valo_core::settings::ServerSettings;
+use valo_core::snapshot::MatchStore;
+
+// Tauri command to change the local server settings, restarting it as needed
+#[tauri::command]
+pub async fn update_server_settings(
+    settings: tauri::State<'_, SettingsState>,
+    store: tauri::State<'_, Arc<MatchStore>>,
+    calendar: tauri::State<'_, Arc<CalendarFeed>>,
+    handle: tauri::State<'_, ServerHandle>,
+    server: ServerSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.server = server.clone())?;
+    restart_server(store.inner().clone(), calendar.inner().clone(), &handle, &server).await
+}
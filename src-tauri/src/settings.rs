@@ -0,0 +1,24 @@
+pub use valo_core::settings::*;
+use tauri::{AppHandle, Manager, Runtime};
+
+// Settings from the app config directory, managed as Tauri state
+pub fn load_state<R: Runtime>(app: &AppHandle<R>) -> SettingsState {
+    let path = app
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(SETTINGS_FILE));
+    SettingsState::load_from(path)
+}
+
+// Tauri command to read the current settings
+#[tauri::command]
+pub fn get_settings(state: tauri::State<'_, SettingsState>) -> Settings {
+    state.get()
+}
+
+// Tauri command to read problems found while loading the settings
+#[tauri::command]
+pub fn get_settings_errors(state: tauri::State<'_, SettingsState>) -> Vec<String> {
+    state.errors().to_vec()
+}
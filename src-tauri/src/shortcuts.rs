@@ -0,0 +1,145 @@
+use crate::settings::{SettingsState, ShortcutSettings};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+#[derive(Debug, Clone, Copy)]
+enum ShortcutAction {
+    ToggleWindow,
+    NextMatch,
+    PreviousMatch,
+}
+
+impl ShortcutAction {
+    fn name(&self) -> &'static str {
+        match self {
+            ShortcutAction::ToggleWindow => "toggle_window",
+            ShortcutAction::NextMatch => "next_match",
+            ShortcutAction::PreviousMatch => "previous_match",
+        }
+    }
+}
+
+// A binding that could not be registered, reported back to the UI
+#[derive(Debug, Serialize, Clone)]
+pub struct ShortcutConflict {
+    pub action: String,
+    pub accelerator: String,
+    pub reason: String,
+}
+
+// Conflicts from the most recent registration, so the UI can read them once it opens
+#[derive(Default)]
+pub struct ShortcutStatus(Mutex<Vec<ShortcutConflict>>);
+
+fn bindings(shortcuts: &ShortcutSettings) -> [(ShortcutAction, Option<&String>); 3] {
+    [
+        (ShortcutAction::ToggleWindow, shortcuts.toggle_window.as_ref()),
+        (ShortcutAction::NextMatch, shortcuts.next_match.as_ref()),
+        (ShortcutAction::PreviousMatch, shortcuts.previous_match.as_ref()),
+    ]
+}
+
+fn run_action<R: Runtime>(app: &AppHandle<R>, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleWindow => crate::toggle_main_window(app),
+        ShortcutAction::NextMatch => {
+            let _ = app.emit("navigate-match", "next");
+        }
+        ShortcutAction::PreviousMatch => {
+            let _ = app.emit("navigate-match", "previous");
+        }
+    }
+}
+
+// Replace all registered shortcuts with the given bindings, returning the ones that failed
+pub fn register_shortcuts<R: Runtime>(
+    app: &AppHandle<R>,
+    shortcuts: &ShortcutSettings,
+) -> Vec<ShortcutConflict> {
+    let manager = app.global_shortcut();
+    if let Err(e) = manager.unregister_all() {
+        eprintln!("Error unregistering shortcuts: {}", e);
+    }
+
+    let mut conflicts = Vec::new();
+    let mut registered: HashMap<u32, &'static str> = HashMap::new();
+
+    for (action, accelerator) in bindings(shortcuts) {
+        let Some(accelerator) = accelerator else {
+            continue;
+        };
+        let conflict = |reason: String| ShortcutConflict {
+            action: action.name().to_string(),
+            accelerator: accelerator.clone(),
+            reason,
+        };
+
+        let shortcut = match accelerator.parse::<Shortcut>() {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                conflicts.push(conflict(format!("Invalid shortcut: {}", e)));
+                continue;
+            }
+        };
+
+        // Two of our own actions on the same keys would shadow each other
+        if let Some(other) = registered.get(&shortcut.id()) {
+            conflicts.push(conflict(format!("Already bound to {}", other)));
+            continue;
+        }
+
+        // Registration fails when another application already owns the shortcut
+        let result = manager.on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                run_action(app, action);
+            }
+        });
+        match result {
+            Ok(()) => {
+                registered.insert(shortcut.id(), action.name());
+            }
+            Err(e) => conflicts.push(conflict(e.to_string())),
+        }
+    }
+
+    conflicts
+}
+
+// Register the saved shortcuts and publish any conflicts to the UI
+pub fn apply_shortcuts<R: Runtime>(
+    app: &AppHandle<R>,
+    status: &ShortcutStatus,
+    shortcuts: &ShortcutSettings,
+) -> Vec<ShortcutConflict> {
+    let conflicts = register_shortcuts(app, shortcuts);
+    for conflict in &conflicts {
+        eprintln!(
+            "Shortcut {} ({}) not registered: {}",
+            conflict.accelerator, conflict.action, conflict.reason
+        );
+    }
+    *status.0.lock().unwrap() = conflicts.clone();
+    let _ = app.emit("shortcut-conflicts", &conflicts);
+    conflicts
+}
+
+// Tauri command to read conflicts from the last registration
+#[tauri::command]
+pub fn get_shortcut_conflicts(status: tauri::State<'_, ShortcutStatus>) -> Vec<ShortcutConflict> {
+    status.0.lock().unwrap().clone()
+}
+
+// Tauri command to change the shortcut bindings (called from React)
+#[tauri::command]
+pub fn update_shortcuts<R: Runtime>(
+    app: AppHandle<R>,
+    settings: tauri::State<'_, SettingsState>,
+    status: tauri::State<'_, ShortcutStatus>,
+    shortcuts: ShortcutSettings,
+) -> Result<Vec<ShortcutConflict>, String> {
+    settings.update(|s| s.shortcuts = shortcuts.clone())?;
+    Ok(apply_shortcuts(&app, &status, &shortcuts))
+}
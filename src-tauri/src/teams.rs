@@ -0,0 +1,26 @@
+use std::sync::Arc;
+use tauri::State;
+use valo_core::logos::logo_src;
+use valo_core::models::TeamProfile;
+use valo_core::snapshot::MatchStore;
+use valo_core::source::SharedSource;
+use valo_core::teams::{with_local_upcoming, ProfileCache, TeamRegistry};
+
+// Tauri command to get a team's roster, recent form and schedule, by vlr.gg id or name
+#[tauri::command]
+pub async fn get_team_profile(
+    team_id: String,
+    source: State<'_, SharedSource>,
+    registry: State<'_, Arc<TeamRegistry>>,
+    profiles: State<'_, Arc<ProfileCache>>,
+    store: State<'_, Arc<MatchStore>>,
+) -> Result<TeamProfile, String> {
+    let snapshot = store.snapshot();
+    let matches: Vec<_> = snapshot.matches.iter().chain(&snapshot.upcoming).cloned().collect();
+    let mut profile = profiles
+        .get(source.as_ref(), &registry, &team_id, &matches, chrono::Utc::now().timestamp())
+        .await
+        .map_err(|e| e.to_string())?;
+    profile.logo = logo_src(&profile.logo, &profile.name);
+    Ok(with_local_upcoming(profile, &snapshot.upcoming))
+}
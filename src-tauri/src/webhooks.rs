@@ -0,0 +1,8 @@
+use valo_core::settings::WebhookEndpoint;
+use valo_core::webhooks::send_test_notification;
+
+// Tauri command to send a sample notification straight to an endpoint
+#[tauri::command]
+pub async fn test_webhook(endpoint: WebhookEndpoint) -> Result<(), String> {
+    send_test_notification(&endpoint).await
+}
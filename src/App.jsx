@@ -1,7 +1,10 @@
-import { useState, useEffect } from 'react'
+import { useState, useEffect, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import MatchCard from './components/MatchCard'
+import MatchHistory from './components/MatchHistory'
+import NewsFeed from './components/NewsFeed'
 import './App.css'
 
 const THEME_KEY = 'valorant-menubar-theme'
@@ -10,7 +13,17 @@ function App() {
   const [matches, setMatches] = useState([])
   const [loading, setLoading] = useState(true)
   const [error, setError] = useState(null)
+  // When the network went down (unix seconds); the last matches stay on screen meanwhile
+  const [offlineSince, setOfflineSince] = useState(null)
   const [currentIndex, setCurrentIndex] = useState(0)
+  const [shortcutConflicts, setShortcutConflicts] = useState([])
+  const [pinnedMatches, setPinnedMatches] = useState([])
+  const [settingsErrors, setSettingsErrors] = useState([])
+  // 'live', 'history' or 'news'
+  const [view, setView] = useState('live')
+  const [filterPresets, setFilterPresets] = useState([])
+  const [filters, setFilters] = useState({ preset: null, rules: [] })
+  const matchesRef = useRef([])
   const [theme, setTheme] = useState(() => {
     try {
       return localStorage.getItem(THEME_KEY) || 'dark'
@@ -33,11 +46,15 @@ function App() {
       setLoading(true)
       setError(null)
       const result = await invoke('get_live_matches')
-      setMatches(result || [])
-      setCurrentIndex((i) => (result?.length ? Math.min(i, result.length - 1) : 0))
+      const live = result?.matches || []
+      setMatches(live)
+      matchesRef.current = live
+      setOfflineSince(result?.offline_since ?? null)
+      setCurrentIndex((i) => (live.length ? Math.min(i, live.length - 1) : 0))
     } catch (err) {
       console.error('Error fetching matches:', err)
-      setError(err.message || 'Failed to load matches')
+      // Command errors arrive as plain strings
+      setError(typeof err === 'string' ? err : err.message || 'Failed to load matches')
     } finally {
       setLoading(false)
     }
@@ -66,6 +83,84 @@ function App() {
     }
   }, [])
 
+  // Global shortcuts: next/previous match and registration conflicts
+  useEffect(() => {
+    invoke('get_shortcut_conflicts')
+      .then((conflicts) => setShortcutConflicts(conflicts || []))
+      .catch(() => {})
+
+    const unlistenNavigate = listen('navigate-match', (event) => {
+      const last = Math.max(0, matchesRef.current.length - 1)
+      setCurrentIndex((i) => (event.payload === 'next' ? Math.min(last, i + 1) : Math.max(0, i - 1)))
+    })
+    const unlistenConflicts = listen('shortcut-conflicts', (event) => {
+      setShortcutConflicts(event.payload || [])
+    })
+
+    return () => {
+      unlistenNavigate.then((unlisten) => unlisten())
+      unlistenConflicts.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  // Tray menu match items open the popover on that match
+  useEffect(() => {
+    const unlisten = listen('select-match', (event) => {
+      const index = matchesRef.current.findIndex((m) => m.match_id === event.payload)
+      if (index >= 0) setCurrentIndex(index)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  // Problems found while loading settings (e.g. invalid templates)
+  useEffect(() => {
+    invoke('get_settings_errors')
+      .then((errors) => setSettingsErrors(errors || []))
+      .catch(() => {})
+  }, [])
+
+  // Filter presets and the current filter settings
+  useEffect(() => {
+    invoke('get_filter_presets')
+      .then((presets) => setFilterPresets(presets || []))
+      .catch(() => {})
+    invoke('get_settings')
+      .then((settings) => settings?.filters && setFilters(settings.filters))
+      .catch(() => {})
+  }, [])
+
+  const changePreset = async (preset) => {
+    try {
+      const updated = await invoke('update_filters', { filters: { ...filters, preset: preset || null } })
+      setFilters(updated)
+      fetchMatches()
+    } catch (err) {
+      console.error('Error updating filters:', err)
+    }
+  }
+
+  // Matches pinned to the mini scoreboard window
+  useEffect(() => {
+    invoke('get_pinned_matches')
+      .then((ids) => setPinnedMatches(ids || []))
+      .catch(() => {})
+    const unlisten = listen('pinned-matches-changed', (event) => setPinnedMatches(event.payload || []))
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const togglePinned = async (matchId) => {
+    try {
+      const ids = await invoke('set_match_pinned', { matchId, pinned: !pinnedMatches.includes(matchId) })
+      setPinnedMatches(ids || [])
+    } catch (err) {
+      console.error('Error pinning match:', err)
+    }
+  }
+
   if (loading && matches.length === 0) {
     return (
       <div className="app-container">
@@ -94,6 +189,7 @@ function App() {
   const hasMultiple = matches.length > 1
   const canGoPrev = hasMultiple && currentIndex > 0
   const canGoNext = hasMultiple && currentIndex < matches.length - 1
+  const isPinned = match ? pinnedMatches.includes(match.match_id) : false
 
   return (
     <div className="app-container">
@@ -104,10 +200,26 @@ function App() {
             <span>Valorant</span>
           </div>
           <div className="header-text">
-            <h1 className="title">Live Match</h1>
+            <h1 className="title">{{ live: 'Live Match', history: 'History', news: 'News' }[view]}</h1>
           </div>
         </div>
         <div className="header-actions">
+          {view === 'live' && filterPresets.length > 0 && (
+            <select
+              className="filter-select"
+              value={filters.preset || ''}
+              onChange={(e) => changePreset(e.target.value)}
+              title="Filter matches"
+              aria-label="Filter matches"
+            >
+              <option value="">All matches</option>
+              {filterPresets.map((preset) => (
+                <option key={preset.id} value={preset.id}>
+                  {preset.label}
+                </option>
+              ))}
+            </select>
+          )}
           <button
             type="button"
             className="theme-toggle icon-button"
@@ -126,6 +238,43 @@ function App() {
               </svg>
             )}
           </button>
+          <button
+            type="button"
+            className={`history-button icon-button ${view === 'history' ? 'active' : ''}`}
+            onClick={() => setView((v) => (v === 'history' ? 'live' : 'history'))}
+            title={view === 'history' ? 'Back to live matches' : 'Match history'}
+            aria-label={view === 'history' ? 'Back to live matches' : 'Match history'}
+          >
+            <svg className="icon" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+              <circle cx="12" cy="12" r="9" />
+              <path d="M12 7v5l3 3" />
+            </svg>
+          </button>
+          <button
+            type="button"
+            className={`news-button icon-button ${view === 'news' ? 'active' : ''}`}
+            onClick={() => setView((v) => (v === 'news' ? 'live' : 'news'))}
+            title={view === 'news' ? 'Back to live matches' : 'News'}
+            aria-label={view === 'news' ? 'Back to live matches' : 'News'}
+          >
+            <svg className="icon" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+              <path d="M4 4h13v16H6a2 2 0 0 1-2-2V4z" />
+              <path d="M17 8h3v10a2 2 0 0 1-2 2M8 8h5M8 12h5M8 16h5" />
+            </svg>
+          </button>
+          {match && view === 'live' && (
+            <button
+              type="button"
+              className={`pin-button icon-button ${isPinned ? 'active' : ''}`}
+              onClick={() => togglePinned(match.match_id)}
+              title={isPinned ? 'Unpin from scoreboard' : 'Pin to scoreboard'}
+              aria-label={isPinned ? 'Unpin from scoreboard' : 'Pin to scoreboard'}
+            >
+              <svg className="icon" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                <path d="M12 17v5M9 3h6l-1 7 4 4H6l4-4-1-7z" />
+              </svg>
+            </button>
+          )}
           <button type="button" onClick={fetchMatches} className="refresh-button icon-button" title="Refresh" aria-label="Refresh">
             <svg className="icon" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
               <path d="M23 4v6h-6" />
@@ -135,17 +284,23 @@ function App() {
         </div>
       </div>
 
-      <div className="matches-list">
-        {matches.length === 0 ? (
-          <div className="empty-state">
-            <p>No live matches at the moment</p>
-          </div>
-        ) : (
-          <MatchCard match={match} />
-        )}
-      </div>
+      {view === 'history' ? (
+        <MatchHistory />
+      ) : view === 'news' ? (
+        <NewsFeed />
+      ) : (
+        <div className="matches-list">
+          {matches.length === 0 ? (
+            <div className="empty-state">
+              <p>{filters.preset || filters.rules.length ? 'No live matches for this filter' : 'No live matches at the moment'}</p>
+            </div>
+          ) : (
+            <MatchCard match={match} />
+          )}
+        </div>
+      )}
 
-      {hasMultiple && (
+      {hasMultiple && view === 'live' && (
         <div className="match-nav">
           <button
             type="button"
@@ -171,6 +326,24 @@ function App() {
         </div>
       )}
 
+      {offlineSince && (
+        <div className="app-warning">
+          ⚠️ Offline since {new Date(offlineSince * 1000).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}, showing the last matches seen
+        </div>
+      )}
+
+      {shortcutConflicts.length > 0 && (
+        <div className="app-warning" title={shortcutConflicts.map((c) => `${c.accelerator}: ${c.reason}`).join('\n')}>
+          ⚠️ {shortcutConflicts.length} shortcut{shortcutConflicts.length === 1 ? '' : 's'} could not be registered
+        </div>
+      )}
+
+      {settingsErrors.length > 0 && (
+        <div className="app-warning" title={settingsErrors.join('\n')}>
+          ⚠️ {settingsErrors.length} settings problem{settingsErrors.length === 1 ? '' : 's'}; using defaults
+        </div>
+      )}
+
       {matches.length > 0 && (
         <div className="app-footer">
           <span className="footer-text">
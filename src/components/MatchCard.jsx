@@ -1,7 +1,21 @@
 import { useState, useEffect } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import TeamProfile from './TeamProfile'
 import './MatchCard.css'
 
+// Time left until `startsAt` (unix seconds), same format as the backend countdown
+const formatCountdown = (startsAt, now) => {
+  const seconds = startsAt - Math.floor(now / 1000)
+  if (seconds <= 0) return 'now'
+  const days = Math.floor(seconds / 86400)
+  const hours = Math.floor((seconds % 86400) / 3600)
+  const minutes = Math.floor((seconds % 3600) / 60)
+  if (days > 0) return `${days}d ${hours}h`
+  if (hours > 0) return `${hours}h ${String(minutes).padStart(2, '0')}m`
+  if (minutes > 0) return `${minutes}m`
+  return '<1m'
+}
+
 function MatchCard({ match }) {
   const isLive = match.time_until_match === 'LIVE'
   const score1 = parseInt(match.score1) || 0
@@ -12,6 +26,20 @@ function MatchCard({ match }) {
   const hasRoundData = rounds1 > 0 || rounds2 > 0
   const [odds, setOdds] = useState(null)
   const [loadingOdds, setLoadingOdds] = useState(false)
+  const [winProbability, setWinProbability] = useState(null)
+  const [now, setNow] = useState(Date.now())
+  const [showPlayers, setShowPlayers] = useState(false)
+  const [playerStats, setPlayerStats] = useState(null)
+  const [playerStatsError, setPlayerStatsError] = useState(null)
+  const [profileTeam, setProfileTeam] = useState(null)
+  const showCountdown = !isLive && match.starts_at != null
+
+  // Tick the countdown while the match hasn't started
+  useEffect(() => {
+    if (!showCountdown) return
+    const timer = setInterval(() => setNow(Date.now()), 30000)
+    return () => clearInterval(timer)
+  }, [showCountdown])
 
   useEffect(() => {
     const fetchOdds = async () => {
@@ -19,7 +47,8 @@ function MatchCard({ match }) {
       try {
         const result = await invoke('get_polymarket_odds', {
           team1: match.team1,
-          team2: match.team2
+          team2: match.team2,
+          startsAt: match.starts_at ?? null
         })
         setOdds(result)
       } catch (err) {
@@ -30,7 +59,31 @@ function MatchCard({ match }) {
     }
     
     fetchOdds()
-  }, [match.team1, match.team2])
+  }, [match.team1, match.team2, match.starts_at])
+
+  // Model estimate from team ratings and the round state, compared with the market once
+  // odds are in. Upcoming matches get the pre-match expectation from the ratings alone.
+  useEffect(() => {
+    invoke('get_win_probability', { segment: match, odds })
+      .then(setWinProbability)
+      .catch((err) => console.error('Error estimating win probability:', err))
+  }, [match, odds])
+
+  // "#3 EMEA", or "Unranked" for teams missing from the cached rankings
+  const formatRank = (rank) => (rank ? `#${rank.rank} ${rank.region_name}` : 'Unranked')
+  // Player stats for the current map, refreshed with the match while the table is open
+  useEffect(() => {
+    if (!showPlayers || !isLive) return
+    invoke('get_player_stats', { segment: match })
+      .then((stats) => {
+        setPlayerStats(stats)
+        setPlayerStatsError(null)
+      })
+      .catch((err) => setPlayerStatsError(String(err)))
+  }, [match, showPlayers, isLive])
+
+  const percent = (value) => `${Math.round(value * 100)}%`
+  const formatEdge = (edge) => `${edge >= 0 ? '+' : ''}${Math.round(edge * 100)} pts`
 
   const formatOdds = (price) => {
     if (!price) return null
@@ -54,6 +107,11 @@ function MatchCard({ match }) {
         <div className="match-info">
           <div className="map-name">{mapLabel}</div>
           <div className="match-series">{match.match_series || match.match_event || 'Series'}</div>
+          {(match.rank1 || match.rank2) && (
+            <div className="match-ranks" title="vlr.gg regional rankings">
+              {formatRank(match.rank1)} vs {formatRank(match.rank2)}
+            </div>
+          )}
         </div>
         {isLive && (
           <div className="live-badge">
@@ -61,6 +119,12 @@ function MatchCard({ match }) {
             LIVE
           </div>
         )}
+        {showCountdown && (
+          <div className="start-time" title={match.start_time}>
+            <span className="start-countdown">{formatCountdown(match.starts_at, now)}</span>
+            <span className="start-clock">{match.start_time}</span>
+          </div>
+        )}
       </div>
 
       <div className="match-teams">
@@ -71,7 +135,18 @@ function MatchCard({ match }) {
             ) : (
               <span className="team-logo team-logo-placeholder" />
             )}
-            <span className="team-name">{match.team1}</span>
+            {match.country1 && (
+              <span className="team-flag" title={match.country1.name}>
+                {match.country1.emoji}
+              </span>
+            )}
+            <button
+              className="team-name"
+              onClick={() => setProfileTeam(profileTeam === match.team1 ? null : match.team1)}
+              title="Roster and schedule"
+            >
+              {match.team1}
+            </button>
           </div>
           <div className="team-score" title="Series (maps won)">{score1}</div>
           {hasRoundData && (
@@ -96,7 +171,18 @@ function MatchCard({ match }) {
             ) : (
               <span className="team-logo team-logo-placeholder" />
             )}
-            <span className="team-name">{match.team2}</span>
+            {match.country2 && (
+              <span className="team-flag" title={match.country2.name}>
+                {match.country2.emoji}
+              </span>
+            )}
+            <button
+              className="team-name"
+              onClick={() => setProfileTeam(profileTeam === match.team2 ? null : match.team2)}
+              title="Roster and schedule"
+            >
+              {match.team2}
+            </button>
           </div>
           <div className="team-score" title="Series (maps won)">{score2}</div>
           {hasRoundData && (
@@ -117,6 +203,59 @@ function MatchCard({ match }) {
       </div>
 
 
+      {winProbability && (
+        <div className="win-probability" title={`Best of ${winProbability.best_of}; model vs Polymarket for ${match.team1}`}>
+          <span>
+            {isLive ? 'Map' : 'Pre-match'} {percent(winProbability.map1)} · Series {percent(winProbability.series1)}
+          </span>
+          {winProbability.edge1 != null && (
+            <span className={`win-edge ${winProbability.edge1 >= 0 ? 'positive' : 'negative'}`}>
+              Edge {formatEdge(winProbability.edge1)}
+            </span>
+          )}
+        </div>
+      )}
+
+      {isLive && match.match_page && (
+        <button className="players-toggle" onClick={() => setShowPlayers(!showPlayers)}>
+          {showPlayers ? 'Hide players' : 'Players'}
+        </button>
+      )}
+
+      {showPlayers && isLive && (
+        <div className="player-stats">
+          {playerStatsError && !playerStats && <div className="player-stats-empty">{playerStatsError}</div>}
+          {!playerStats && !playerStatsError && <div className="player-stats-empty">Loading...</div>}
+          {playerStats && [playerStats.team1, playerStats.team2].map((team) => (
+            <table key={team.team} className="player-table">
+              <thead>
+                <tr>
+                  <th>{team.team}</th>
+                  <th title="Average Combat Score">ACS</th>
+                  <th>K / D / A</th>
+                  <th title="Average Damage per Round">ADR</th>
+                </tr>
+              </thead>
+              <tbody>
+                {team.players.map((player) => (
+                  <tr key={player.name}>
+                    <td>
+                      <span className="player-name">{player.name}</span>
+                      <span className="player-agents">{player.agents.join(', ')}</span>
+                    </td>
+                    <td>{player.acs ?? '—'}</td>
+                    <td>{player.kills ?? '—'} / {player.deaths ?? '—'} / {player.assists ?? '—'}</td>
+                    <td>{player.adr ?? '—'}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          ))}
+        </div>
+      )}
+
+      {profileTeam && <TeamProfile team={profileTeam} onClose={() => setProfileTeam(null)} />}
+
       {odds?.market_url && (
         <a 
           href={odds.market_url} 
@@ -0,0 +1,83 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import './MatchHistory.css'
+
+// Convert a yyyy-mm-dd input value to a unix timestamp (start or end of that day)
+const toTimestamp = (value, endOfDay) => {
+  if (!value) return null
+  const date = new Date(`${value}T${endOfDay ? '23:59:59' : '00:00:00'}`)
+  return Number.isNaN(date.getTime()) ? null : Math.floor(date.getTime() / 1000)
+}
+
+const formatDate = (timestamp) =>
+  new Date(timestamp * 1000).toLocaleDateString(undefined, { month: 'short', day: 'numeric' })
+
+function MatchHistory() {
+  const [team, setTeam] = useState('')
+  const [event, setEvent] = useState('')
+  const [from, setFrom] = useState('')
+  const [to, setTo] = useState('')
+  const [records, setRecords] = useState([])
+  const [error, setError] = useState(null)
+
+  useEffect(() => {
+    // Debounce typing in the filters
+    const timeout = setTimeout(() => {
+      invoke('get_match_history', {
+        filter: {
+          team: team || null,
+          event: event || null,
+          from: toTimestamp(from, false),
+          to: toTimestamp(to, true),
+          limit: 50,
+        },
+      })
+        .then((result) => {
+          setRecords(result || [])
+          setError(null)
+        })
+        .catch((err) => setError(String(err)))
+    }, 250)
+    return () => clearTimeout(timeout)
+  }, [team, event, from, to])
+
+  return (
+    <div className="match-history">
+      <div className="history-filters">
+        <input type="text" placeholder="Team" value={team} onChange={(e) => setTeam(e.target.value)} />
+        <input type="text" placeholder="Event" value={event} onChange={(e) => setEvent(e.target.value)} />
+        <input type="date" value={from} onChange={(e) => setFrom(e.target.value)} aria-label="From" />
+        <input type="date" value={to} onChange={(e) => setTo(e.target.value)} aria-label="To" />
+      </div>
+
+      {error && <div className="history-empty">{error}</div>}
+      {!error && records.length === 0 && <div className="history-empty">No recorded matches</div>}
+
+      <div className="history-list">
+        {records.map((record) => (
+          <div key={record.match_id} className="history-row">
+            <div className="history-teams">
+              <span className={record.winner === record.team1 ? 'history-winner' : ''}>{record.team1}</span>
+              <span className="history-score">
+                {record.score1} - {record.score2}
+              </span>
+              <span className={record.winner === record.team2 ? 'history-winner' : ''}>{record.team2}</span>
+            </div>
+            <div className="history-meta">
+              <span>{formatDate(record.started_at)}</span>
+              <span className="history-event">{record.event}</span>
+              {record.ended_at === null && <span className="history-live">LIVE</span>}
+            </div>
+            {record.maps.length > 0 && (
+              <div className="history-maps">
+                {record.maps.map((map) => `${map.map} ${map.rounds1}-${map.rounds2}`).join(' · ')}
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  )
+}
+
+export default MatchHistory
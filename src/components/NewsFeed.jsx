@@ -0,0 +1,51 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import './NewsFeed.css'
+
+// Cached vlr.gg headlines; the backend refreshes them every 10 minutes
+function NewsFeed() {
+  const [followedOnly, setFollowedOnly] = useState(false)
+  const [items, setItems] = useState([])
+  const [error, setError] = useState(null)
+
+  useEffect(() => {
+    const fetchNews = () =>
+      invoke('get_news', { followedOnly })
+        .then((result) => {
+          setItems(result || [])
+          setError(null)
+        })
+        .catch((err) => setError(String(err)))
+    fetchNews()
+    const timer = setInterval(fetchNews, 60000)
+    return () => clearInterval(timer)
+  }, [followedOnly])
+
+  return (
+    <div className="news-feed">
+      <label className="news-filter">
+        <input type="checkbox" checked={followedOnly} onChange={(e) => setFollowedOnly(e.target.checked)} />
+        Followed teams only
+      </label>
+
+      {error && <div className="news-empty">{error}</div>}
+      {!error && items.length === 0 && (
+        <div className="news-empty">{followedOnly ? 'No news about followed teams' : 'No news yet'}</div>
+      )}
+
+      <div className="news-list">
+        {items.map((item) => (
+          <a key={item.url} href={item.url} target="_blank" rel="noopener noreferrer" className="news-row">
+            <div className="news-title">{item.title}</div>
+            <div className="news-meta">
+              <span>{item.date}</span>
+              {item.teams.length > 0 && <span className="news-teams">{item.teams.join(', ')}</span>}
+            </div>
+          </a>
+        ))}
+      </div>
+    </div>
+  )
+}
+
+export default NewsFeed
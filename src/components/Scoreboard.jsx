@@ -0,0 +1,80 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import './Scoreboard.css'
+
+const THEME_KEY = 'valorant-menubar-theme'
+
+function Scoreboard() {
+  const [matches, setMatches] = useState([])
+  const [pinned, setPinned] = useState([])
+
+  useEffect(() => {
+    try {
+      document.documentElement.setAttribute('data-theme', localStorage.getItem(THEME_KEY) || 'dark')
+    } catch {}
+
+    const fetchMatches = async () => {
+      try {
+        const result = await invoke('get_live_matches')
+        setMatches(result?.matches || [])
+      } catch (err) {
+        console.error('Error fetching matches:', err)
+      }
+    }
+
+    fetchMatches()
+    invoke('get_pinned_matches')
+      .then((ids) => setPinned(ids || []))
+      .catch(() => {})
+
+    const interval = setInterval(fetchMatches, 30000)
+    const unlisten = listen('pinned-matches-changed', (event) => setPinned(event.payload || []))
+    return () => {
+      clearInterval(interval)
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  // With nothing pinned, follow the first live match
+  const shown = pinned.length > 0
+    ? matches.filter((m) => pinned.includes(m.match_id))
+    : matches.slice(0, 1)
+
+  const unpin = (matchId) => {
+    invoke('set_match_pinned', { matchId, pinned: false }).catch(() => {})
+  }
+
+  return (
+    <div className="scoreboard" data-tauri-drag-region>
+      {shown.length === 0 ? (
+        <div className="scoreboard-empty" data-tauri-drag-region>No live matches</div>
+      ) : (
+        shown.map((match) => {
+          const rounds1 = (parseInt(match.team1_round_ct) || 0) + (parseInt(match.team1_round_t) || 0)
+          const rounds2 = (parseInt(match.team2_round_ct) || 0) + (parseInt(match.team2_round_t) || 0)
+          return (
+            <div className="scoreboard-row" key={match.match_id} data-tauri-drag-region>
+              <span className="scoreboard-team" data-tauri-drag-region>{match.team1}</span>
+              <span className="scoreboard-score" data-tauri-drag-region>
+                {match.score1} - {match.score2}
+              </span>
+              <span className="scoreboard-team scoreboard-team-right" data-tauri-drag-region>{match.team2}</span>
+              <span className="scoreboard-map" data-tauri-drag-region>
+                {match.current_map || 'Unknown Map'}
+                {(rounds1 > 0 || rounds2 > 0) && ` (${rounds1}-${rounds2})`}
+              </span>
+              {pinned.includes(match.match_id) && (
+                <button type="button" className="scoreboard-unpin" onClick={() => unpin(match.match_id)} aria-label="Unpin match">
+                  ×
+                </button>
+              )}
+            </div>
+          )
+        })
+      )}
+    </div>
+  )
+}
+
+export default Scoreboard
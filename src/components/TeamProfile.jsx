@@ -0,0 +1,70 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import './TeamProfile.css'
+
+// Roster, recent form and schedule of a team, opened from a match card
+function TeamProfile({ team, onClose }) {
+  const [profile, setProfile] = useState(null)
+  const [error, setError] = useState(null)
+
+  useEffect(() => {
+    setProfile(null)
+    setError(null)
+    invoke('get_team_profile', { teamId: team })
+      .then(setProfile)
+      .catch((err) => setError(String(err)))
+  }, [team])
+
+  return (
+    <div className="team-profile">
+      <div className="team-profile-header">
+        {profile?.logo && <img src={profile.logo} alt="" className="team-logo" />}
+        <span className="team-profile-name">{profile?.name || team}</span>
+        {profile?.form && (
+          <span className="team-form" title="Latest results, most recent first">
+            {profile.form.split('').map((result, i) => (
+              <span key={i} className={result === 'W' ? 'form-win' : 'form-loss'}>{result}</span>
+            ))}
+          </span>
+        )}
+        <button className="team-profile-close" onClick={onClose} aria-label="Close team profile">×</button>
+      </div>
+
+      {error && <div className="team-profile-empty">{error}</div>}
+      {!profile && !error && <div className="team-profile-empty">Loading...</div>}
+
+      {profile && (
+        <>
+          <div className="team-profile-section">Roster</div>
+          {profile.roster.length === 0 && <div className="team-profile-empty">No roster listed</div>}
+          {profile.roster.map((member) => (
+            <div key={member.alias} className="team-profile-row">
+              <span className="player-name">{member.alias}</span>
+              <span className="team-profile-muted">{member.role || member.real_name}</span>
+            </div>
+          ))}
+
+          <div className="team-profile-section">Recent</div>
+          {profile.recent_matches.length === 0 && <div className="team-profile-empty">No recent matches</div>}
+          {profile.recent_matches.map((m) => (
+            <div key={m.match_page || `${m.opponent}-${m.date}`} className="team-profile-row">
+              <span>vs {m.opponent}</span>
+              <span className="team-profile-muted">{m.score}</span>
+            </div>
+          ))}
+
+          <div className="team-profile-section">Upcoming</div>
+          {profile.upcoming_matches.length === 0 && <div className="team-profile-empty">Nothing scheduled</div>}
+          {profile.upcoming_matches.map((m) => (
+            <div key={m.match_page || `${m.opponent}-${m.date}`} className="team-profile-row">
+              <span>vs {m.opponent}</span>
+              <span className="team-profile-muted">{m.date}</span>
+            </div>
+          ))}
+        </>
+      )}
+    </div>
+  )
+}
+
+export default TeamProfile
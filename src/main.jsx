@@ -1,10 +1,15 @@
 import React from 'react'
 import ReactDOM from 'react-dom/client'
+import { getCurrentWindow } from '@tauri-apps/api/window'
 import App from './App'
+import Scoreboard from './components/Scoreboard'
 import './index.css'
 
+// The mini scoreboard window loads the same page; pick the view by window label
+const isScoreboard = getCurrentWindow().label === 'scoreboard'
+
 ReactDOM.createRoot(document.getElementById('root')).render(
   <React.StrictMode>
-    <App />
+    {isScoreboard ? <Scoreboard /> : <App />}
   </React.StrictMode>,
 )